@@ -17,6 +17,8 @@ fn main() {
     link_libraries(&sdk_path);
 
     println!("cargo:rerun-if-env-changed=ECOS_SDK_HOME");
+    println!("cargo:rerun-if-env-changed=ECOS_PSRAM_ARENA_SIZE");
+    println!("cargo:rerun-if-env-changed=ECOS_PSRAM_REGION");
 }
 
 fn scan_sdk_directories(sdk_path: &Path) -> (Vec<PathBuf>, Vec<PathBuf>) {
@@ -138,7 +140,12 @@ fn link_libraries(sdk_path: &Path) {
         let out_dir = env::var("OUT_DIR").expect("OUT_DIR not set");
         let dest_lds = PathBuf::from(&out_dir).join("sections.lds");
 
-        let content = fs::read_to_string(&sections_lds).expect("Failed to read linker script");
+        let mut content = fs::read_to_string(&sections_lds).expect("Failed to read linker script");
+
+        if env::var_os("CARGO_FEATURE_PSRAM").is_some() {
+            let sections = psram_sections(&content);
+            content.push_str(&sections);
+        }
 
         fs::write(&dest_lds, content).expect("Failed to write modified linker script");
 
@@ -174,3 +181,100 @@ fn link_libraries(sdk_path: &Path) {
     println!("cargo:rustc-link-arg=-Wl,--gc-sections");
     println!("cargo:rustc-link-arg=-nostartfiles");
 }
+
+/// `.psram`段：放`psram_static!`定义的对象，其后预留`PsramArena`的区域
+///
+/// 段为NOLOAD，放进SDK链接脚本MEMORY中的PSRAM区域：优先用`ECOS_PSRAM_REGION`
+/// 指定的区域，否则找名字含`PSRAM`的区域；都没有时构建失败，避免段落进片上SRAM。
+/// 区域范围导出为`_psram_base`/`_psram_end`。
+fn psram_sections(script: &str) -> String {
+    let arena_size = env::var("ECOS_PSRAM_ARENA_SIZE")
+        .ok()
+        .map(|size| parse_number(&size).expect("Invalid ECOS_PSRAM_ARENA_SIZE"))
+        .unwrap_or(256 * 1024);
+
+    let region = psram_region(script);
+
+    format!(
+        "
+SECTIONS
+{{
+    _psram_base = ORIGIN({region});
+    _psram_end = ORIGIN({region}) + LENGTH({region});
+
+    .psram (NOLOAD) : ALIGN(8)
+    {{
+        *(.psram .psram.*)
+        . = ALIGN(8);
+        _psram_arena_start = .;
+        . += {arena_size};
+        _psram_arena_end = .;
+    }} > {region}
+}}
+INSERT AFTER .bss;
+"
+    )
+}
+
+/// 在链接脚本的MEMORY中找PSRAM区域的名字
+fn psram_region(script: &str) -> String {
+    let regions = memory_regions(script);
+
+    if let Ok(region) = env::var("ECOS_PSRAM_REGION") {
+        let region = region.trim();
+        if !regions.iter().any(|name| name == region) {
+            panic!(
+                "ECOS_PSRAM_REGION `{region}` is not declared in the MEMORY of sections.lds \
+                 (declared: {regions:?})"
+            );
+        }
+        return region.to_string();
+    }
+
+    let mut found = regions
+        .iter()
+        .filter(|name| name.to_ascii_uppercase().contains("PSRAM"));
+    match (found.next(), found.next()) {
+        (Some(region), None) => region.clone(),
+        (Some(_), Some(_)) => panic!(
+            "Several PSRAM regions in the MEMORY of sections.lds ({regions:?}), \
+             set ECOS_PSRAM_REGION to the one to use"
+        ),
+        (None, _) => panic!(
+            "No region named like PSRAM in the MEMORY of sections.lds ({regions:?}); \
+             set ECOS_PSRAM_REGION to the region mapped to PSRAM, or build without the `psram` feature"
+        ),
+    }
+}
+
+/// MEMORY中声明的区域名
+fn memory_regions(script: &str) -> Vec<String> {
+    let Some(start) = script.find("MEMORY") else {
+        return Vec::new();
+    };
+    let body = &script[start..];
+    let (Some(open), Some(close)) = (body.find('{'), body.find('}')) else {
+        return Vec::new();
+    };
+
+    body[open + 1..close]
+        .lines()
+        .filter_map(|line| {
+            let (name, _) = line.split_once(':')?;
+            let name = name.split(['(', ' ', '\t']).find(|part| !part.is_empty())?;
+            Some(name.to_string())
+        })
+        .collect()
+}
+
+/// 十进制或`0x`开头的十六进制
+fn parse_number(value: &str) -> Option<usize> {
+    let value = value.trim();
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
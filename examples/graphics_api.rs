@@ -10,7 +10,8 @@ use embedded_graphics::{
     text::Text,
 };
 
-use ecos_ebui::{St7735Config, St7735Manager};
+use ecos_ebui::st7735::Panel;
+use ecos_ebui::{FrameSync, St7735Config, St7735Manager};
 
 #[ecos_main(tick)]
 fn main() -> ! {
@@ -18,12 +19,9 @@ fn main() -> ! {
 
     // 配置显示参数
     let config = St7735Config {
-        dc_pin: 14,
-        rst_pin: None,
-        width: 128,
-        height: 128,
-        rgb: true, // 使用 RGB 模式
-        inverted: false,
+        rgb: true,                    // 使用 RGB 模式
+        frame_sync: FrameSync::Paced, // 未接TE，按帧率节流防撕裂
+        ..St7735Config::preset(Panel::Tft144_128x128)
     };
 
     // 创建显示管理器
@@ -69,8 +67,7 @@ fn basic_shapes_demo(manager: &mut St7735Manager) {
 
     // 1. 清屏为黑色
     manager.display.clear(Rgb565::BLACK).unwrap();
-    #[cfg(feature = "st7735-lcd-doublebuffering")]
-    manager.display.swap_buffers().unwrap();
+    manager.flush().unwrap();
 
     manager.delay.delay_ms(1000);
 
@@ -81,8 +78,7 @@ fn basic_shapes_demo(manager: &mut St7735Manager) {
         .draw(&mut manager.display)
         .unwrap();
 
-    #[cfg(feature = "st7735-lcd-doublebuffering")]
-    manager.display.swap_buffers().unwrap();
+    manager.flush().unwrap();
 
     manager.delay.delay_ms(1000);
 
@@ -93,8 +89,7 @@ fn basic_shapes_demo(manager: &mut St7735Manager) {
         .draw(&mut manager.display)
         .unwrap();
 
-    #[cfg(feature = "st7735-lcd-doublebuffering")]
-    manager.display.swap_buffers().unwrap();
+    manager.flush().unwrap();
 
     manager.delay.delay_ms(1000);
 
@@ -105,8 +100,7 @@ fn basic_shapes_demo(manager: &mut St7735Manager) {
         .draw(&mut manager.display)
         .unwrap();
 
-    #[cfg(feature = "st7735-lcd-doublebuffering")]
-    manager.display.swap_buffers().unwrap();
+    manager.flush().unwrap();
 
     manager.delay.delay_ms(1000);
 
@@ -117,8 +111,7 @@ fn basic_shapes_demo(manager: &mut St7735Manager) {
         .draw(&mut manager.display)
         .unwrap();
 
-    #[cfg(feature = "st7735-lcd-doublebuffering")]
-    manager.display.swap_buffers().unwrap();
+    manager.flush().unwrap();
 
     manager.delay.delay_ms(1000);
 }
@@ -131,8 +124,7 @@ fn text_and_animation_demo(manager: &mut St7735Manager) {
 
     // 清屏为深蓝色
     manager.display.clear(Rgb565::new(0, 0, 15)).unwrap();
-    #[cfg(feature = "st7735-lcd-doublebuffering")]
-    manager.display.swap_buffers().unwrap();
+    manager.flush().unwrap();
 
     manager.delay.delay_ms(500);
 
@@ -156,8 +148,7 @@ fn text_and_animation_demo(manager: &mut St7735Manager) {
         .draw(&mut manager.display)
         .unwrap();
 
-    #[cfg(feature = "st7735-lcd-doublebuffering")]
-    manager.display.swap_buffers().unwrap();
+    manager.flush().unwrap();
 
     manager.delay.delay_ms(1000);
 
@@ -178,8 +169,7 @@ fn text_and_animation_demo(manager: &mut St7735Manager) {
             .draw(&mut manager.display)
             .unwrap();
 
-        #[cfg(feature = "st7735-lcd-doublebuffering")]
-        manager.display.swap_buffers().unwrap();
+        manager.flush().unwrap();
 
         manager.delay.delay_ms(30);
     }
@@ -227,8 +217,7 @@ fn dynamic_effects_demo(manager: &mut St7735Manager) {
             .draw(&mut manager.display)
             .unwrap();
 
-        #[cfg(feature = "st7735-lcd-doublebuffering")]
-        manager.display.swap_buffers().unwrap();
+        manager.flush().unwrap();
 
         manager.delay.delay_ms(50);
     }
@@ -5,7 +5,8 @@ use ecos_ssc1::ecos_main;
 use embedded_graphics::pixelcolor::Bgr565;
 use embedded_graphics::prelude::*;
 
-use ecos_ebui::adapter::st7735::{St7735Config, St7735Manager};
+use ecos_ebui::st7735::Panel;
+use ecos_ebui::{St7735Config, St7735Manager};
 
 #[ecos_main(tick)]
 fn main() -> ! {
@@ -39,14 +40,8 @@ fn init_display() -> Result<St7735Manager, &'static str> {
     println!("初始化ST7735显示屏...");
 
     // 配置显示参数
-    let config = St7735Config {
-        dc_pin: 14,    // DC引脚
-        rst_pin: None, // 无硬件复位
-        width: 128,
-        height: 128,
-        rgb: false, // BGR模式
-        inverted: false,
-    };
+    // 1.44寸 128x128 模组：BGR模式，无硬件复位，DC接排针14
+    let config = St7735Config::preset(Panel::Tft144_128x128);
 
     // 使用管理器创建显示驱动
     println!("创建显示管理器...");
@@ -92,13 +87,10 @@ fn color_loop_test(mut manager: St7735Manager) -> ! {
                 continue;
             }
 
-            // 如果是双缓冲版本，需要交换缓冲区
-            #[cfg(feature = "st7735-lcd-doublebuffering")]
-            {
-                if let Err(e) = manager.display.swap_buffers() {
-                    println!("交换缓冲区失败: {:?}", e);
-                    continue;
-                }
+            // 缓冲模式下交换缓冲区，直接模式下为空操作
+            if let Err(e) = manager.flush() {
+                println!("刷新失败: {:?}", e);
+                continue;
             }
 
             use embedded_hal::delay::DelayNs;
@@ -4,6 +4,9 @@
 use ecos_ssc1::bindings::*;
 use ecos_ssc1::ecos_main;
 
+use ecos_ebui::ColorMode;
+use ecos_ebui::st7735::cmd::{Caset, Colmod, Command, CommandParams, Madctl, Raset};
+
 #[ecos_main(tick)]
 fn main() -> ! {
     println!("ST7735 QSPI 四色显示测试开始");
@@ -46,23 +49,29 @@ fn main() -> ! {
         }
     }
 
+    // 辅助函数：发送带类型参数的命令
+    fn write_params<P: CommandParams>(dc_pin: gpio_num_t, params: P) {
+        write_cmd(dc_pin, P::COMMAND.code());
+        for &byte in params.params().as_ref() {
+            write_data(dc_pin, byte);
+        }
+    }
+
     // 初始延迟
     ecos_ssc1::Timer::delay_ms(120);
 
     // 睡眠退出
-    write_cmd(dc_pin, 0x11);
+    write_cmd(dc_pin, Command::SlpOut.code());
     ecos_ssc1::Timer::delay_ms(120);
 
     // 设置颜色模式 (16位RGB565)
-    write_cmd(dc_pin, 0x3A);
-    write_data(dc_pin, 0x05); // 65k模式
+    write_params(dc_pin, Colmod(ColorMode::Rgb565)); // 65k模式
 
     // 设置显示方向（竖屏）
-    write_cmd(dc_pin, 0x36);
-    write_data(dc_pin, 0xC8); // 竖屏
+    write_params(dc_pin, Madctl::MY | Madctl::MX | Madctl::BGR); // 竖屏
 
     // 开启显示
-    write_cmd(dc_pin, 0x29);
+    write_cmd(dc_pin, Command::DispOn.code());
     ecos_ssc1::Timer::delay_ms(100);
 
     println!("ST7735初始化完成");
@@ -75,22 +84,12 @@ fn main() -> ! {
         let y0 = y0 + 3;
         let y1 = y1 + 3;
 
-        // 设置列地址 (0x2A)
-        write_cmd(dc_pin, 0x2A);
-        write_data(dc_pin, (x0 >> 8) as u8);
-        write_data(dc_pin, (x0 & 0xFF) as u8);
-        write_data(dc_pin, (x1 >> 8) as u8);
-        write_data(dc_pin, (x1 & 0xFF) as u8);
-
-        // 设置行地址 (0x2B)
-        write_cmd(dc_pin, 0x2B);
-        write_data(dc_pin, (y0 >> 8) as u8);
-        write_data(dc_pin, (y0 & 0xFF) as u8);
-        write_data(dc_pin, (y1 >> 8) as u8);
-        write_data(dc_pin, (y1 & 0xFF) as u8);
-
-        // 开始内存写入 (0x2C)
-        write_cmd(dc_pin, 0x2C);
+        // 设置列地址与行地址
+        write_params(dc_pin, Caset::new(x0, x1));
+        write_params(dc_pin, Raset::new(y0, y1));
+
+        // 开始内存写入
+        write_cmd(dc_pin, Command::RamWr.code());
     }
 
     // 5. 定义颜色（16位RGB565）
@@ -3,6 +3,9 @@
 
 use ecos_ssc1::{Timer, ecos_main, gpio, qspi};
 
+use ecos_ebui::st7735::cmd::{Colmod, Command, Madctl, RawPanel};
+use ecos_ebui::{ColorMode, SpiError};
+
 #[ecos_main(tick)]
 fn main() -> ! {
     println!("ST7735 QSPI 四色显示测试开始");
@@ -35,79 +38,53 @@ fn main() -> ! {
     );
     println!("DC引脚配置为输出模式 (pin {})", dc_pin_num);
 
+    // 创建底层面板接口：命令字节与参数由类型化的命令层给出
+    let mut panel = match RawPanel::with_pins(dc_pin_num, None) {
+        Ok(panel) => panel,
+        Err(e) => {
+            println!("创建面板接口失败: {:?}", e);
+            loop {}
+        }
+    };
+
     // 4. ST7735简单初始化
     println!("ST7735初始化中...");
 
-    // 辅助函数：发送命令
-    fn write_cmd(qspi: &mut qspi::Qspi, dc_pin: u32, cmd: u8) -> Result<(), qspi::QspiError> {
-        gpio::GpioPin::set_level(dc_pin, false);
-        qspi.write_u8(cmd)
-    }
-
-    // 辅助函数：发送数据
-    fn write_data(qspi: &mut qspi::Qspi, dc_pin: u32, data: u8) -> Result<(), qspi::QspiError> {
-        gpio::GpioPin::set_level(dc_pin, true);
-        qspi.write_u8(data)
-    }
-
     // 初始延迟
     println!("初始延迟...");
     Timer::delay_ms(120);
 
     // 睡眠退出
     println!("发送睡眠退出命令...");
-    let _ = write_cmd(qspi_instance, dc_pin_num, 0x11);
+    let _ = panel.command(Command::SlpOut, &[]);
     Timer::delay_ms(120);
 
     // 设置颜色模式 (16位RGB565)
     println!("设置颜色模式...");
-    let _ = write_cmd(qspi_instance, dc_pin_num, 0x3A);
-    let _ = write_data(qspi_instance, dc_pin_num, 0x05); // 65k模式
+    let _ = panel.send(Colmod(ColorMode::Rgb565));
 
-    // 设置显示方向（竖屏）
+    // 设置显示方向（竖屏，行列倒序 + BGR）
     println!("设置显示方向...");
-    let _ = write_cmd(qspi_instance, dc_pin_num, 0x36);
-    let _ = write_data(qspi_instance, dc_pin_num, 0xC8); // 竖屏
+    let _ = panel.send(Madctl::MY | Madctl::MX | Madctl::BGR);
 
     // 开启显示
     println!("开启显示...");
-    let _ = write_cmd(qspi_instance, dc_pin_num, 0x29);
+    let _ = panel.command(Command::DispOn, &[]);
     Timer::delay_ms(100);
 
     println!("ST7735初始化完成");
 
-    // 5. 定义设置窗口函数
+    // 5. 定义设置窗口函数：设置窗口后开始写显存，DC保持为数据
     fn set_window(
-        qspi: &mut qspi::Qspi,
-        dc_pin: u32,
+        panel: &mut RawPanel,
         x0: u16,
         y0: u16,
         x1: u16,
         y1: u16,
-    ) -> Result<(), qspi::QspiError> {
+    ) -> Result<(), SpiError> {
         // 添加偏移（与之前C代码一致）
-        let x0 = x0 + 2;
-        let x1 = x1 + 2;
-        let y0 = y0 + 3;
-        let y1 = y1 + 3;
-
-        // 设置列地址 (0x2A)
-        write_cmd(qspi, dc_pin, 0x2A)?;
-        write_data(qspi, dc_pin, (x0 >> 8) as u8)?;
-        write_data(qspi, dc_pin, (x0 & 0xFF) as u8)?;
-        write_data(qspi, dc_pin, (x1 >> 8) as u8)?;
-        write_data(qspi, dc_pin, (x1 & 0xFF) as u8)?;
-
-        // 设置行地址 (0x2B)
-        write_cmd(qspi, dc_pin, 0x2B)?;
-        write_data(qspi, dc_pin, (y0 >> 8) as u8)?;
-        write_data(qspi, dc_pin, (y0 & 0xFF) as u8)?;
-        write_data(qspi, dc_pin, (y1 >> 8) as u8)?;
-        write_data(qspi, dc_pin, (y1 & 0xFF) as u8)?;
-
-        // 开始内存写入 (0x2C)
-        write_cmd(qspi, dc_pin, 0x2C)?;
-        Ok(())
+        panel.set_window(x0 + 2, y0 + 3, x1 + 2, y1 + 3)?;
+        panel.begin_write()
     }
 
     // 6. 定义颜色（16位RGB565）
@@ -132,89 +109,73 @@ fn main() -> ! {
             0 => {
                 println!("当前颜色：黑白红绿");
                 // 左上黑 (0-63, 0-63)
-                set_window(qspi_instance, dc_pin_num, 0, 0, 63, 63).unwrap();
-                gpio::GpioPin::set_level(dc_pin_num, true);
+                set_window(&mut panel, 0, 0, 63, 63).unwrap();
                 qspi_instance.write_words(&black_data).unwrap();
 
                 // 右上白 (64-127, 0-63)
-                set_window(qspi_instance, dc_pin_num, 64, 0, 127, 63).unwrap();
-                gpio::GpioPin::set_level(dc_pin_num, true);
+                set_window(&mut panel, 64, 0, 127, 63).unwrap();
                 qspi_instance.write_words(&white_data).unwrap();
 
                 // 左下红 (0-63, 64-127)
-                set_window(qspi_instance, dc_pin_num, 0, 64, 63, 127).unwrap();
-                gpio::GpioPin::set_level(dc_pin_num, true);
+                set_window(&mut panel, 0, 64, 63, 127).unwrap();
                 qspi_instance.write_words(&red_data).unwrap();
 
                 // 右下绿 (64-127, 64-127)
-                set_window(qspi_instance, dc_pin_num, 64, 64, 127, 127).unwrap();
-                gpio::GpioPin::set_level(dc_pin_num, true);
+                set_window(&mut panel, 64, 64, 127, 127).unwrap();
                 qspi_instance.write_words(&green_data).unwrap();
             }
             1 => {
                 println!("当前颜色：绿黑白红");
                 // 左上绿 (0-63, 0-63)
-                set_window(qspi_instance, dc_pin_num, 0, 0, 63, 63).unwrap();
-                gpio::GpioPin::set_level(dc_pin_num, true);
+                set_window(&mut panel, 0, 0, 63, 63).unwrap();
                 qspi_instance.write_words(&green_data).unwrap();
 
                 // 右上黑 (64-127, 0-63)
-                set_window(qspi_instance, dc_pin_num, 64, 0, 127, 63).unwrap();
-                gpio::GpioPin::set_level(dc_pin_num, true);
+                set_window(&mut panel, 64, 0, 127, 63).unwrap();
                 qspi_instance.write_words(&black_data).unwrap();
 
                 // 左下白 (0-63, 64-127)
-                set_window(qspi_instance, dc_pin_num, 0, 64, 63, 127).unwrap();
-                gpio::GpioPin::set_level(dc_pin_num, true);
+                set_window(&mut panel, 0, 64, 63, 127).unwrap();
                 qspi_instance.write_words(&white_data).unwrap();
 
                 // 右下红 (64-127, 64-127)
-                set_window(qspi_instance, dc_pin_num, 64, 64, 127, 127).unwrap();
-                gpio::GpioPin::set_level(dc_pin_num, true);
+                set_window(&mut panel, 64, 64, 127, 127).unwrap();
                 qspi_instance.write_words(&red_data).unwrap();
             }
             2 => {
                 println!("当前颜色：红绿黑白");
                 // 左上红 (0-63, 0-63)
-                set_window(qspi_instance, dc_pin_num, 0, 0, 63, 63).unwrap();
-                gpio::GpioPin::set_level(dc_pin_num, true);
+                set_window(&mut panel, 0, 0, 63, 63).unwrap();
                 qspi_instance.write_words(&red_data).unwrap();
 
                 // 右上绿 (64-127, 0-63)
-                set_window(qspi_instance, dc_pin_num, 64, 0, 127, 63).unwrap();
-                gpio::GpioPin::set_level(dc_pin_num, true);
+                set_window(&mut panel, 64, 0, 127, 63).unwrap();
                 qspi_instance.write_words(&green_data).unwrap();
 
                 // 左下黑 (0-63, 64-127)
-                set_window(qspi_instance, dc_pin_num, 0, 64, 63, 127).unwrap();
-                gpio::GpioPin::set_level(dc_pin_num, true);
+                set_window(&mut panel, 0, 64, 63, 127).unwrap();
                 qspi_instance.write_words(&black_data).unwrap();
 
                 // 右下白 (64-127, 64-127)
-                set_window(qspi_instance, dc_pin_num, 64, 64, 127, 127).unwrap();
-                gpio::GpioPin::set_level(dc_pin_num, true);
+                set_window(&mut panel, 64, 64, 127, 127).unwrap();
                 qspi_instance.write_words(&white_data).unwrap();
             }
             3 => {
                 println!("当前颜色：白红绿黑");
                 // 左上白 (0-63, 0-63)
-                set_window(qspi_instance, dc_pin_num, 0, 0, 63, 63).unwrap();
-                gpio::GpioPin::set_level(dc_pin_num, true);
+                set_window(&mut panel, 0, 0, 63, 63).unwrap();
                 qspi_instance.write_words(&white_data).unwrap();
 
                 // 右上红 (64-127, 0-63)
-                set_window(qspi_instance, dc_pin_num, 64, 0, 127, 63).unwrap();
-                gpio::GpioPin::set_level(dc_pin_num, true);
+                set_window(&mut panel, 64, 0, 127, 63).unwrap();
                 qspi_instance.write_words(&red_data).unwrap();
 
                 // 左下绿 (0-63, 64-127)
-                set_window(qspi_instance, dc_pin_num, 0, 64, 63, 127).unwrap();
-                gpio::GpioPin::set_level(dc_pin_num, true);
+                set_window(&mut panel, 0, 64, 63, 127).unwrap();
                 qspi_instance.write_words(&green_data).unwrap();
 
                 // 右下黑 (64-127, 64-127)
-                set_window(qspi_instance, dc_pin_num, 64, 64, 127, 127).unwrap();
-                gpio::GpioPin::set_level(dc_pin_num, true);
+                set_window(&mut panel, 64, 64, 127, 127).unwrap();
                 qspi_instance.write_words(&black_data).unwrap();
             }
             _ => {}
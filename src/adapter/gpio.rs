@@ -1,11 +1,17 @@
 use core::fmt::Debug;
 use core::prelude::rust_2024::derive;
-use core::{result::Result, result::Result::Ok};
+use core::{
+    option::Option,
+    option::Option::{None, Some},
+    result::Result,
+    result::Result::Ok,
+};
 
 use ecos_ssc1::GpioPin;
 use embedded_hal::digital::Error;
 use embedded_hal::digital::ErrorKind;
 use embedded_hal::digital::ErrorType;
+use embedded_hal::digital::InputPin;
 use embedded_hal::digital::OutputPin;
 
 #[derive(Debug)]
@@ -17,6 +23,17 @@ impl EbdHalGpio {
     pub fn new(pin: u32) -> Self {
         Self { pin }
     }
+
+    /// 将引脚配置为输入后返回
+    pub fn input(pin: u32) -> Self {
+        if (1..=16).contains(&pin) {
+            GpioPin::config_pins(
+                1 << (pin - 1),
+                ecos_ssc1::bindings::gpio_mode_t_GPIO_MODE_INPUT,
+            );
+        }
+        Self { pin }
+    }
 }
 
 impl Error for EbdHalGpio {
@@ -40,3 +57,72 @@ impl OutputPin for EbdHalGpio {
         Ok(())
     }
 }
+
+impl InputPin for EbdHalGpio {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        GpioPin::get_level(self.pin).ok_or(ErrorKind::Other)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        self.is_high().map(|level| !level)
+    }
+}
+
+/// 未连接的引脚：所有操作都是空操作
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoPin;
+
+impl ErrorType for NoPin {
+    type Error = ErrorKind;
+}
+
+impl OutputPin for NoPin {
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// 可选引脚：已接线时驱动GPIO，未接线时为[`NoPin`]
+#[derive(Debug)]
+pub enum OptionalPin {
+    Gpio(EbdHalGpio),
+    NoPin(NoPin),
+}
+
+impl OptionalPin {
+    pub fn new(pin: Option<u32>) -> Self {
+        match pin {
+            Some(pin) => OptionalPin::Gpio(EbdHalGpio::new(pin)),
+            None => OptionalPin::NoPin(NoPin),
+        }
+    }
+
+    /// 是否接了实际的引脚
+    pub fn is_connected(&self) -> bool {
+        matches!(self, OptionalPin::Gpio(_))
+    }
+}
+
+impl ErrorType for OptionalPin {
+    type Error = ErrorKind;
+}
+
+impl OutputPin for OptionalPin {
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        match self {
+            OptionalPin::Gpio(pin) => pin.set_high(),
+            OptionalPin::NoPin(pin) => pin.set_high(),
+        }
+    }
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        match self {
+            OptionalPin::Gpio(pin) => pin.set_low(),
+            OptionalPin::NoPin(pin) => pin.set_low(),
+        }
+    }
+}
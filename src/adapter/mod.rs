@@ -1,7 +1,13 @@
 pub mod delay;
 pub mod gpio;
+pub mod pixel;
 pub mod spi;
+pub mod three_wire;
 
 pub use delay::EbdHalDelay;
-pub use gpio::EbdHalGpio;
-pub use spi::{EbdHalSpiDevice, SpiError, create_spi_device, create_spi_device_with_cs};
+pub use gpio::{EbdHalGpio, NoPin, OptionalPin};
+pub use pixel::{ColorMode, PixelPacker, Rgb444};
+pub use spi::{
+    ConfigError, EbdHalSpiDevice, SpiError, create_spi_device, create_spi_device_with_cs,
+};
+pub use three_wire::ThreeWireSpi;
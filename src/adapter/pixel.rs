@@ -0,0 +1,274 @@
+use core::any::Any;
+
+use embedded_graphics_core::pixelcolor::{
+    IntoStorage, PixelColor, Rgb565, Rgb666, Rgb888, RgbColor, raw::RawU16,
+};
+
+/// 控制器接口像素格式（COLMOD）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorMode {
+    /// 12位，两个像素占3字节，总线流量比RGB565少25%
+    Rgb444,
+    /// 16位，每像素2字节
+    #[default]
+    Rgb565,
+    /// 18位，每像素3字节，每个分量占字节的高6位
+    Rgb666,
+}
+
+impl ColorMode {
+    /// COLMOD参数
+    pub fn colmod(self) -> u8 {
+        match self {
+            ColorMode::Rgb444 => 0x03,
+            ColorMode::Rgb565 => 0x05,
+            ColorMode::Rgb666 => 0x06,
+        }
+    }
+
+    /// 每像素位数
+    pub fn bits_per_pixel(self) -> u8 {
+        match self {
+            ColorMode::Rgb444 => 12,
+            ColorMode::Rgb565 => 16,
+            ColorMode::Rgb666 => 18,
+        }
+    }
+
+    /// `bytes`字节能容纳的完整像素数（RGB444按像素对计）
+    pub fn pixels_in(self, bytes: usize) -> usize {
+        match self {
+            ColorMode::Rgb444 => bytes / 3 * 2,
+            ColorMode::Rgb565 => bytes / 2,
+            ColorMode::Rgb666 => bytes / 3,
+        }
+    }
+
+    /// `pixels`个像素在总线上的字节数
+    pub fn bytes_for(self, pixels: usize) -> usize {
+        match self {
+            ColorMode::Rgb444 => (pixels * 3).div_ceil(2),
+            ColorMode::Rgb565 => pixels * 2,
+            ColorMode::Rgb666 => pixels * 3,
+        }
+    }
+}
+
+/// 12位RGB颜色，对应[`ColorMode::Rgb444`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgb444(u16);
+
+impl Rgb444 {
+    /// 各分量取低4位
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self(((r as u16 & 0x0F) << 8) | ((g as u16 & 0x0F) << 4) | (b as u16 & 0x0F))
+    }
+}
+
+impl PixelColor for Rgb444 {
+    type Raw = RawU16;
+}
+
+impl RgbColor for Rgb444 {
+    fn r(&self) -> u8 {
+        (self.0 >> 8) as u8 & 0x0F
+    }
+
+    fn g(&self) -> u8 {
+        (self.0 >> 4) as u8 & 0x0F
+    }
+
+    fn b(&self) -> u8 {
+        self.0 as u8 & 0x0F
+    }
+
+    const MAX_R: u8 = 0x0F;
+    const MAX_G: u8 = 0x0F;
+    const MAX_B: u8 = 0x0F;
+
+    const BLACK: Self = Self::new(0, 0, 0);
+    const RED: Self = Self::new(0x0F, 0, 0);
+    const GREEN: Self = Self::new(0, 0x0F, 0);
+    const BLUE: Self = Self::new(0, 0, 0x0F);
+    const YELLOW: Self = Self::new(0x0F, 0x0F, 0);
+    const MAGENTA: Self = Self::new(0x0F, 0, 0x0F);
+    const CYAN: Self = Self::new(0, 0x0F, 0x0F);
+    const WHITE: Self = Self::new(0x0F, 0x0F, 0x0F);
+}
+
+impl From<Rgb888> for Rgb444 {
+    fn from(color: Rgb888) -> Self {
+        Self::new(color.r() >> 4, color.g() >> 4, color.b() >> 4)
+    }
+}
+
+impl From<Rgb565> for Rgb444 {
+    fn from(color: Rgb565) -> Self {
+        Rgb888::from(color).into()
+    }
+}
+
+impl From<Rgb666> for Rgb444 {
+    fn from(color: Rgb666) -> Self {
+        Rgb888::from(color).into()
+    }
+}
+
+impl From<Rgb444> for Rgb888 {
+    fn from(color: Rgb444) -> Self {
+        // 0x0F * 17 = 0xFF
+        Rgb888::new(color.r() * 17, color.g() * 17, color.b() * 17)
+    }
+}
+
+impl From<Rgb444> for Rgb565 {
+    fn from(color: Rgb444) -> Self {
+        Rgb888::from(color).into()
+    }
+}
+
+impl From<Rgb444> for Rgb666 {
+    fn from(color: Rgb444) -> Self {
+        Rgb888::from(color).into()
+    }
+}
+
+/// 像素打包：按颜色模式把像素流转换为总线字节
+///
+/// RGB444两个像素共用3字节，奇数像素暂存到下一个像素到来，
+/// 流结束时调用[`PixelPacker::finish`]写出剩余部分。
+#[derive(Debug, Clone, Copy)]
+pub struct PixelPacker {
+    mode: ColorMode,
+    pending: Option<Rgb444>,
+}
+
+impl PixelPacker {
+    pub fn new(mode: ColorMode) -> Self {
+        Self {
+            mode,
+            pending: None,
+        }
+    }
+
+    /// 颜色模式
+    pub fn mode(&self) -> ColorMode {
+        self.mode
+    }
+
+    /// 追加一个像素，产生的字节写入`out`，返回字节数（0~3）
+    pub fn push<C: Into<Rgb888> + 'static>(&mut self, color: C, out: &mut [u8; 3]) -> usize {
+        // RGB565写入RGB565模式时直接取存储值，不经RGB888换算
+        if self.mode == ColorMode::Rgb565
+            && let Some(color) = (&color as &dyn Any).downcast_ref::<Rgb565>()
+        {
+            out[..2].copy_from_slice(&color.into_storage().to_be_bytes());
+            return 2;
+        }
+
+        let color: Rgb888 = color.into();
+        match self.mode {
+            ColorMode::Rgb444 => {
+                let color = Rgb444::from(color);
+                match self.pending.take() {
+                    None => {
+                        self.pending = Some(color);
+                        0
+                    }
+                    Some(first) => {
+                        *out = [
+                            (first.r() << 4) | first.g(),
+                            (first.b() << 4) | color.r(),
+                            (color.g() << 4) | color.b(),
+                        ];
+                        3
+                    }
+                }
+            }
+            ColorMode::Rgb565 => {
+                let [hi, lo] = Rgb565::from(color).into_storage().to_be_bytes();
+                out[0] = hi;
+                out[1] = lo;
+                2
+            }
+            ColorMode::Rgb666 => {
+                let color = Rgb666::from(color);
+                *out = [color.r() << 2, color.g() << 2, color.b() << 2];
+                3
+            }
+        }
+    }
+
+    /// 结束像素流：RGB444剩下单个像素时补齐为2字节
+    pub fn finish(&mut self, out: &mut [u8; 3]) -> usize {
+        match self.pending.take() {
+            Some(last) => {
+                out[0] = (last.r() << 4) | last.g();
+                out[1] = last.b() << 4;
+                2
+            }
+            None => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 打包整个像素流并结束，返回总线字节
+    fn pack<C: Into<Rgb888> + 'static>(
+        mode: ColorMode,
+        colors: impl IntoIterator<Item = C>,
+    ) -> heapless::Vec<u8, 32> {
+        let mut packer = PixelPacker::new(mode);
+        let mut bytes = heapless::Vec::new();
+        let mut out = [0u8; 3];
+        for color in colors {
+            let n = packer.push(color, &mut out);
+            bytes.extend_from_slice(&out[..n]).unwrap();
+        }
+        let n = packer.finish(&mut out);
+        bytes.extend_from_slice(&out[..n]).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn rgb565_is_big_endian_storage() {
+        let colors = [Rgb565::new(0x1F, 0, 0x01), Rgb565::new(0, 0x3F, 0)];
+        assert_eq!(pack(ColorMode::Rgb565, colors), [0xF8, 0x01, 0x07, 0xE0]);
+
+        // 经RGB888换算的慢路径结果一致
+        let colors = colors.map(Rgb888::from);
+        assert_eq!(pack(ColorMode::Rgb565, colors), [0xF8, 0x01, 0x07, 0xE0]);
+    }
+
+    #[test]
+    fn rgb444_packs_pairs_into_three_bytes() {
+        let colors = [Rgb444::new(0x1, 0x2, 0x3), Rgb444::new(0x4, 0x5, 0x6)];
+        assert_eq!(pack(ColorMode::Rgb444, colors), [0x12, 0x34, 0x56]);
+    }
+
+    #[test]
+    fn rgb444_odd_pixel_is_padded_on_finish() {
+        let colors = [
+            Rgb444::new(0x1, 0x2, 0x3),
+            Rgb444::new(0x4, 0x5, 0x6),
+            Rgb444::new(0xA, 0xB, 0xC),
+        ];
+        assert_eq!(
+            pack(ColorMode::Rgb444, colors),
+            [0x12, 0x34, 0x56, 0xAB, 0xC0]
+        );
+        assert_eq!(ColorMode::Rgb444.bytes_for(3), 5);
+
+        let mut packer = PixelPacker::new(ColorMode::Rgb444);
+        assert_eq!(packer.finish(&mut [0; 3]), 0);
+    }
+
+    #[test]
+    fn rgb666_uses_high_six_bits() {
+        let colors = [Rgb666::new(0x3F, 0x01, 0x20)];
+        assert_eq!(pack(ColorMode::Rgb666, colors), [0xFC, 0x04, 0x80]);
+    }
+}
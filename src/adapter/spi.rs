@@ -21,6 +21,10 @@ use super::delay::EbdHalDelay;
 use super::gpio::EbdHalGpio;
 use ecos_ssc1::{Qspi, QspiError, qspi};
 
+/// 像素流每次写入的字节数：QSPI发送FIFO为32个字，一次不超过128字节；
+/// 取3与4的公倍数，RGB444/666的像素不跨批拆分
+pub(crate) const BURST_LEN: usize = 96;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SpiError {
     Timeout,
@@ -28,6 +32,52 @@ pub enum SpiError {
     TransferFailed,
     GpioError,
     NotInitialized,
+    /// 所需功能未编译进来（对应特性未启用）
+    Unsupported,
+    /// 配置有误，未访问硬件
+    InvalidConfig(ConfigError),
+}
+
+/// 配置错误，由`validate()`在访问硬件之前给出
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError {
+    /// 引脚不在排针号1–16范围内
+    PinOutOfRange { pin: u32 },
+    /// 同一引脚被配置为多个功能，或多块屏共用同一片选
+    DuplicatePin { pin: u32 },
+    /// 同一总线上接多块屏，但有屏未设置片选
+    MissingChipSelect,
+    /// 宽或高为0
+    EmptySize,
+    /// 显存尺寸超出控制器上限
+    GramTooLarge {
+        width: u16,
+        height: u16,
+        max_width: u16,
+        max_height: u16,
+    },
+    /// 列偏移加宽度超出显存宽度
+    ColumnOverflow {
+        offset: u16,
+        width: u16,
+        gram_width: u16,
+    },
+    /// 行偏移加高度超出显存高度
+    RowOverflow {
+        offset: u16,
+        height: u16,
+        gram_height: u16,
+    },
+    /// 压缩初始化脚本在`offset`处截断
+    MalformedInitScript { offset: usize },
+    /// 缓冲模式只支持4线接口与RGB565
+    BufferedModeUnsupported,
+}
+
+impl From<ConfigError> for SpiError {
+    fn from(error: ConfigError) -> Self {
+        SpiError::InvalidConfig(error)
+    }
 }
 
 impl From<QspiError> for SpiError {
@@ -49,29 +99,37 @@ impl embedded_hal::spi::Error for SpiError {
 }
 
 // ========== EbdHalSpiDevice ==========
+/// QSPI总线上的一个设备
+///
+/// 不长期持有全局QSPI，每次事务时才取用，
+/// 因此多个设备（各自片选）可以共享同一条总线。
 pub struct EbdHalSpiDevice {
-    qspi: &'static mut Qspi,
     cs_pin: Option<EbdHalGpio>,
 }
 
 impl EbdHalSpiDevice {
     /// 创建新的 SPI 设备 - 无片选
     pub fn new() -> Option<Self> {
-        qspi::get_qspi().map(|qspi| Self { qspi, cs_pin: None })
+        Self::with_optional_cs(None)
     }
 
     /// 使用片选引脚创建 SPI 设备
     pub fn with_cs_pin(cs_pin_num: u32) -> Option<Self> {
-        qspi::get_qspi().map(|qspi| {
-            let mut cs_pin = EbdHalGpio::new(cs_pin_num);
+        Self::with_optional_cs(Some(cs_pin_num))
+    }
+
+    /// 按可选片选创建 SPI 设备，QSPI未初始化时返回`None`
+    pub fn with_optional_cs(cs_pin_num: Option<u32>) -> Option<Self> {
+        qspi::get_qspi()?;
+
+        let cs_pin = cs_pin_num.map(|pin| {
+            let mut cs_pin = EbdHalGpio::new(pin);
             // 默认设置 CS 为高电平
             let _ = cs_pin.set_high();
+            cs_pin
+        });
 
-            Self {
-                qspi,
-                cs_pin: Some(cs_pin),
-            }
-        })
+        Some(Self { cs_pin })
     }
 
     /// 激活片选
@@ -93,14 +151,17 @@ impl EbdHalSpiDevice {
     }
 
     /// 执行单个操作
-    fn execute_operation(&mut self, operation: &mut Operation<'_, u8>) -> Result<(), SpiError> {
+    fn execute_operation(
+        qspi: &mut Qspi,
+        operation: &mut Operation<'_, u8>,
+    ) -> Result<(), SpiError> {
         match operation {
             Operation::Write(data) => {
                 // 单个字节使用优化的写入
                 if data.len() == 1 {
-                    self.qspi.write_u8(data[0])?;
+                    qspi.write_u8(data[0])?;
                 } else {
-                    self.qspi.write_bytes(data)?;
+                    qspi.write_bytes(data)?;
                 }
                 Ok(())
             }
@@ -109,9 +170,9 @@ impl EbdHalSpiDevice {
                 // 发送虚拟数据并尝试读取
                 for i in 0..buffer.len() {
                     // 发送虚拟字节
-                    self.qspi.write_u8(0x00)?;
+                    qspi.write_u8(0x00)?;
                     // 读取响应
-                    let word = self.qspi.read_u32();
+                    let word = qspi.read_u32();
                     buffer[i] = (word >> 24) as u8; // 取最高字节
                 }
                 Ok(())
@@ -120,9 +181,9 @@ impl EbdHalSpiDevice {
                 // 写入数据
                 if !write.is_empty() {
                     if write.len() == 1 {
-                        self.qspi.write_u8(write[0])?;
+                        qspi.write_u8(write[0])?;
                     } else {
-                        self.qspi.write_bytes(write)?;
+                        qspi.write_bytes(write)?;
                     }
                 }
 
@@ -130,9 +191,9 @@ impl EbdHalSpiDevice {
                 if !read.is_empty() {
                     for i in 0..read.len() {
                         // 发送虚拟字节
-                        self.qspi.write_u8(0x00)?;
+                        qspi.write_u8(0x00)?;
                         // 读取响应
-                        let word = self.qspi.read_u32();
+                        let word = qspi.read_u32();
                         read[i] = (word >> 24) as u8;
                     }
                 }
@@ -144,17 +205,17 @@ impl EbdHalSpiDevice {
 
                 // 写入数据
                 if write_data.len() == 1 {
-                    self.qspi.write_u8(write_data[0])?;
+                    qspi.write_u8(write_data[0])?;
                 } else {
-                    self.qspi.write_bytes(&write_data)?;
+                    qspi.write_bytes(&write_data)?;
                 }
 
                 // 读取数据到同一个缓冲区
                 for i in 0..buffer.len() {
                     // 发送虚拟字节
-                    self.qspi.write_u8(0x00)?;
+                    qspi.write_u8(0x00)?;
                     // 读取响应
-                    let word = self.qspi.read_u32();
+                    let word = qspi.read_u32();
                     buffer[i] = (word >> 24) as u8;
                 }
                 Ok(())
@@ -173,16 +234,18 @@ impl ErrorType for EbdHalSpiDevice {
 
 impl SpiDevice<u8> for EbdHalSpiDevice {
     fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+        let qspi = qspi::get_qspi().ok_or(SpiError::NotInitialized)?;
+
         // 激活片选
         self.cs_select()?;
 
         // 执行所有操作
         for operation in operations.iter_mut() {
-            self.execute_operation(operation)?;
+            Self::execute_operation(qspi, operation)?;
         }
 
         // 等待所有传输完成
-        self.qspi.wait_transfer_complete_full()?;
+        qspi.wait_transfer_complete_full()?;
 
         // 取消片选
         self.cs_deselect()
@@ -0,0 +1,97 @@
+use embedded_hal::spi::SpiDevice;
+
+use super::spi::{EbdHalSpiDevice, SpiError};
+
+/// 每组9位字的个数：8个9位字正好9字节，可按字节经QSPI发出
+const GROUP_WORDS: usize = 8;
+const GROUP_BYTES: usize = 9;
+
+/// 一次QSPI写入的组数（90字节，不超过发送FIFO的128字节）
+const GROUPS_PER_WRITE: usize = 10;
+
+/// 补齐用的NOP命令（D/C=0, 0x00）
+const NOP_WORD: u16 = 0x000;
+
+/// 3线SPI（9位）传输：每个字节前加一位D/C，不需要DC引脚
+///
+/// 9位字按8个一组打包为9字节发出；不足一组的部分暂存，
+/// 在[`ThreeWireSpi::flush`]时用NOP补齐。NOP会结束正在进行的RAMWR，
+/// 因此像素流中途不能flush。
+pub struct ThreeWireSpi {
+    spi: EbdHalSpiDevice,
+    pending: [u16; GROUP_WORDS],
+    pending_len: usize,
+    out: [u8; GROUP_BYTES * GROUPS_PER_WRITE],
+    out_len: usize,
+}
+
+impl ThreeWireSpi {
+    pub fn new(spi: EbdHalSpiDevice) -> Self {
+        Self {
+            spi,
+            pending: [0; GROUP_WORDS],
+            pending_len: 0,
+            out: [0; GROUP_BYTES * GROUPS_PER_WRITE],
+            out_len: 0,
+        }
+    }
+
+    /// 写入命令字节（D/C=0）
+    pub fn write_command(&mut self, command: u8) -> Result<(), SpiError> {
+        self.push(command as u16)
+    }
+
+    /// 写入数据字节（D/C=1）
+    pub fn write_data(&mut self, data: &[u8]) -> Result<(), SpiError> {
+        for &byte in data {
+            self.push(0x100 | byte as u16)?;
+        }
+        Ok(())
+    }
+
+    /// 用NOP补齐最后一组并发出所有暂存数据
+    pub fn flush(&mut self) -> Result<(), SpiError> {
+        if self.pending_len > 0 {
+            while self.pending_len < GROUP_WORDS {
+                self.pending[self.pending_len] = NOP_WORD;
+                self.pending_len += 1;
+            }
+            self.pack_group();
+        }
+        self.write_out()
+    }
+
+    fn push(&mut self, word: u16) -> Result<(), SpiError> {
+        self.pending[self.pending_len] = word;
+        self.pending_len += 1;
+
+        if self.pending_len == GROUP_WORDS {
+            self.pack_group();
+            if self.out_len == self.out.len() {
+                self.write_out()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 把8个9位字按高位在前拼成9字节
+    fn pack_group(&mut self) {
+        let mut bits: u128 = 0;
+        for &word in &self.pending {
+            bits = (bits << 9) | (word & 0x1FF) as u128;
+        }
+        let bytes = bits.to_be_bytes();
+        self.out[self.out_len..self.out_len + GROUP_BYTES]
+            .copy_from_slice(&bytes[16 - GROUP_BYTES..]);
+        self.out_len += GROUP_BYTES;
+        self.pending_len = 0;
+    }
+
+    fn write_out(&mut self) -> Result<(), SpiError> {
+        if self.out_len > 0 {
+            self.spi.write(&self.out[..self.out_len])?;
+            self.out_len = 0;
+        }
+        Ok(())
+    }
+}
@@ -1,5 +1,24 @@
+pub mod multi;
+pub mod panel;
+pub mod power;
+pub mod probe;
+pub mod scroll;
 pub mod st7735;
+pub mod tuning;
+pub mod vsync;
 
+pub use multi::MultiDisplay;
+pub use panel::{
+    DisplayManager, Orientation, Panel, Rotation, WindowWrite, init_default_panel, init_panel,
+};
+pub use power::{
+    AutoDim, AutoDimConfig, Backlight, DimStage, GpioBacklight, PowerState, PwmBacklight,
+};
+pub use probe::{Controller, PanelId, probe};
+pub use scroll::{ScrollArea, ScrollView, VerticalScroller};
 pub use st7735::{
-    St7735Builder, St7735Config, St7735Display, St7735Manager, init_default_display, init_display,
+    ColorView, DisplayMode, EncodedScript, InitScript, InitStep, SpiInterface, St7735Builder,
+    St7735Config, St7735Display, St7735Manager, init_default_display, init_display,
 };
+pub use tuning::{FrameRates, Gamma, PowerControl};
+pub use vsync::{FrameControl, FrameSync};
@@ -0,0 +1,428 @@
+use embedded_graphics_core::{
+    Pixel,
+    draw_target::DrawTarget,
+    geometry::{Dimensions, Point, Size},
+    pixelcolor::Rgb565,
+    primitives::Rectangle,
+};
+use heapless::Vec;
+
+use super::panel::{DisplayManager, Panel, WindowWrite, init_panel};
+use crate::adapter::delay::EbdHalDelay;
+use crate::adapter::spi::{ConfigError, SpiError};
+
+/// 广播时每批缓存的像素数
+const CHUNK: usize = 64;
+
+/// 按行广播时每批转发的行数（只缓存行切片，不拷贝像素）
+const ROW_BATCH: usize = 32;
+
+/// 同一条QSPI总线上的多块屏
+///
+/// 各屏通过自己的CS/DC引脚区分，配置互相独立。
+/// 作为[`DrawTarget`]使用时，同一次绘制广播到所有屏（镜像显示）；
+/// 作为[`WindowWrite`]使用时，帧缓冲的同一批行依次发给每块屏，
+/// 例如`fb.flush(&mut multi)`把一块[`crate::gfx::Framebuffer`]镜像到所有屏。
+/// 需要分别绘制时用[`MultiDisplay::get_mut`]取出单块屏。
+pub struct MultiDisplay<P: Panel, const N: usize> {
+    panels: [P; N],
+    pub delay: EbdHalDelay,
+}
+
+impl<P: Panel, const N: usize> MultiDisplay<P, N> {
+    /// 按各自的配置创建所有屏，QSPI只初始化一次
+    ///
+    /// 多于一块屏时每块都必须有自己的`cs_pin`，否则各屏会收到彼此的数据。
+    pub fn new(configs: [P::Config; N]) -> Result<Self, SpiError> {
+        check_chip_selects::<P>(&configs)?;
+
+        let mut panels: Vec<P, N> = Vec::new();
+        for config in configs {
+            let _ = panels.push(init_panel(config)?);
+        }
+        let panels = panels
+            .into_array()
+            .map_err(|_| SpiError::InvalidParameter)?;
+
+        Ok(Self {
+            panels,
+            delay: EbdHalDelay,
+        })
+    }
+
+    /// 初始化所有屏
+    pub fn init(&mut self) -> Result<(), SpiError> {
+        for panel in &mut self.panels {
+            panel.init(&mut self.delay)?;
+        }
+        Ok(())
+    }
+
+    /// 刷新所有屏
+    pub fn flush(&mut self) -> Result<(), SpiError> {
+        for panel in &mut self.panels {
+            panel.flush()?;
+        }
+        Ok(())
+    }
+
+    /// 第`index`块屏
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut P> {
+        self.panels.get_mut(index)
+    }
+
+    /// 全部屏
+    pub fn panels_mut(&mut self) -> &mut [P; N] {
+        &mut self.panels
+    }
+
+    /// 拆成各自独立的显示管理器
+    pub fn into_managers(self) -> [DisplayManager<P>; N] {
+        self.panels.map(DisplayManager::from_panel)
+    }
+
+    fn broadcast_pixels(&mut self, pixels: &[Pixel<P::Color>]) -> Result<(), P::Error> {
+        for panel in &mut self.panels {
+            panel.draw_iter(pixels.iter().copied())?;
+        }
+        Ok(())
+    }
+}
+
+/// 多屏共享总线时片选引脚必须各不相同且都已设置
+fn check_chip_selects<P: Panel>(configs: &[P::Config]) -> Result<(), ConfigError> {
+    if configs.len() < 2 {
+        return Ok(());
+    }
+    for (index, config) in configs.iter().enumerate() {
+        let pin = P::chip_select(config).ok_or(ConfigError::MissingChipSelect)?;
+        if configs[index + 1..]
+            .iter()
+            .any(|other| P::chip_select(other) == Some(pin))
+        {
+            return Err(ConfigError::DuplicatePin { pin });
+        }
+    }
+    Ok(())
+}
+
+impl<P: Panel, const N: usize> Dimensions for MultiDisplay<P, N> {
+    /// 以第一块屏的尺寸为准，其余屏按各自边界裁剪
+    fn bounding_box(&self) -> Rectangle {
+        let (width, height) = self.panels.first().map_or((0, 0), |p| p.logical_size());
+        Rectangle::new(Point::zero(), Size::new(width as u32, height as u32))
+    }
+}
+
+impl<P: Panel, const N: usize> DrawTarget for MultiDisplay<P, N> {
+    type Color = P::Color;
+    type Error = P::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        // 迭代器只能消费一次，分批缓存后依次发给每块屏
+        let mut chunk: Vec<Pixel<Self::Color>, CHUNK> = Vec::new();
+        for pixel in pixels {
+            if chunk.push(pixel).is_err() {
+                self.broadcast_pixels(&chunk)?;
+                chunk.clear();
+                let _ = chunk.push(pixel);
+            }
+        }
+        self.broadcast_pixels(&chunk)
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        if area.size.width == 0 || area.size.height == 0 {
+            return Ok(());
+        }
+
+        // 按行切成不超过CHUNK的小段，每段仍可走各屏的连续填充
+        let mut colors = colors.into_iter();
+        let width = area.size.width;
+        for y in 0..area.size.height as i32 {
+            let mut x = 0;
+            while x < width {
+                let mut run: Vec<Self::Color, CHUNK> = Vec::new();
+                while (run.len() as u32) < (width - x).min(CHUNK as u32) {
+                    match colors.next() {
+                        Some(color) => {
+                            let _ = run.push(color);
+                        }
+                        None => break,
+                    }
+                }
+                if run.is_empty() {
+                    return Ok(());
+                }
+
+                let top_left = area.top_left + Point::new(x as i32, y);
+                let piece = Rectangle::new(top_left, Size::new(run.len() as u32, 1));
+                for panel in &mut self.panels {
+                    panel.fill_contiguous(&piece, run.iter().copied())?;
+                }
+                x += run.len() as u32;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        for panel in &mut self.panels {
+            panel.fill_solid(area, color)?;
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        for panel in &mut self.panels {
+            panel.clear(color)?;
+        }
+        Ok(())
+    }
+}
+
+/// 按窗口写入时镜像到所有屏
+impl<P: Panel + WindowWrite, const N: usize> WindowWrite for MultiDisplay<P, N> {
+    type Error = <P as WindowWrite>::Error;
+
+    fn write_window<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Rgb565>,
+    {
+        // 像素流只能消费一次，按行切成不超过CHUNK的小段依次发给每块屏
+        let mut colors = colors.into_iter();
+        let width = area.size.width;
+        for y in 0..area.size.height as i32 {
+            let mut x = 0;
+            while x < width {
+                let run: Vec<Rgb565, CHUNK> = colors
+                    .by_ref()
+                    .take((width - x).min(CHUNK as u32) as usize)
+                    .collect();
+                if run.is_empty() {
+                    return Ok(());
+                }
+
+                let top_left = area.top_left + Point::new(x as i32, y);
+                let piece = Rectangle::new(top_left, Size::new(run.len() as u32, 1));
+                for panel in &mut self.panels {
+                    panel.write_window(&piece, run.iter().copied())?;
+                }
+                x += run.len() as u32;
+            }
+        }
+        Ok(())
+    }
+
+    /// 同一批行切片依次交给每块屏的`write_rows`，保留各屏的整行批量发送
+    fn write_rows<'a, R>(&mut self, area: &Rectangle, rows: R) -> Result<(), Self::Error>
+    where
+        R: IntoIterator<Item = &'a [Rgb565]>,
+    {
+        let mut rows = rows.into_iter().take(area.size.height as usize);
+        let mut y = area.top_left.y;
+        loop {
+            let batch: Vec<&[Rgb565], ROW_BATCH> = rows.by_ref().take(ROW_BATCH).collect();
+            if batch.is_empty() {
+                return Ok(());
+            }
+
+            let band = Rectangle::new(
+                Point::new(area.top_left.x, y),
+                Size::new(area.size.width, batch.len() as u32),
+            );
+            for panel in &mut self.panels {
+                panel.write_rows(&band, batch.iter().copied())?;
+            }
+            y += batch.len() as i32;
+        }
+    }
+
+    /// 依次等待每块屏，各屏可能有各自的TE
+    fn wait_frame(&mut self) -> Result<(), Self::Error> {
+        for panel in &mut self.panels {
+            panel.wait_frame()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::convert::Infallible;
+
+    use embedded_graphics_core::{geometry::OriginDimensions, pixelcolor::RgbColor};
+    use embedded_hal::delay::DelayNs;
+
+    use super::*;
+    use crate::driver::panel::Orientation;
+    use crate::driver::power::PowerState;
+    use crate::gfx::Framebuffer;
+
+    const WIDTH: usize = 8;
+    const HEIGHT: usize = ROW_BATCH + 4;
+
+    /// 记录屏幕内容与窗口写入次数的假面板
+    struct MockPanel {
+        screen: [[Rgb565; WIDTH]; HEIGHT],
+        windows: usize,
+    }
+
+    impl MockPanel {
+        fn new() -> Self {
+            Self {
+                screen: [[Rgb565::BLACK; WIDTH]; HEIGHT],
+                windows: 0,
+            }
+        }
+    }
+
+    impl Panel for MockPanel {
+        type Config = Option<u32>;
+
+        fn create(_: Option<u32>) -> Result<Self, SpiError> {
+            Ok(Self::new())
+        }
+
+        fn chip_select(config: &Option<u32>) -> Option<u32> {
+            *config
+        }
+
+        fn init<D: DelayNs>(&mut self, _: &mut D) -> Result<(), SpiError> {
+            Ok(())
+        }
+
+        fn orientation(&self) -> Orientation {
+            Orientation::default()
+        }
+
+        fn set_orientation(&mut self, _: Orientation) -> Result<(), SpiError> {
+            Ok(())
+        }
+
+        fn power_state(&self) -> PowerState {
+            PowerState::On
+        }
+
+        fn set_power_state<D: DelayNs>(
+            &mut self,
+            _: PowerState,
+            _: &mut D,
+        ) -> Result<(), SpiError> {
+            Ok(())
+        }
+
+        fn flush(&mut self) -> Result<(), SpiError> {
+            Ok(())
+        }
+
+        fn logical_size(&self) -> (u16, u16) {
+            (WIDTH as u16, HEIGHT as u16)
+        }
+    }
+
+    impl OriginDimensions for MockPanel {
+        fn size(&self) -> Size {
+            Size::new(WIDTH as u32, HEIGHT as u32)
+        }
+    }
+
+    impl DrawTarget for MockPanel {
+        type Color = Rgb565;
+        type Error = Infallible;
+
+        fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Pixel<Rgb565>>,
+        {
+            for Pixel(point, color) in pixels {
+                self.screen[point.y as usize][point.x as usize] = color;
+            }
+            Ok(())
+        }
+    }
+
+    impl WindowWrite for MockPanel {
+        type Error = Infallible;
+
+        fn write_window<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Infallible>
+        where
+            I: IntoIterator<Item = Rgb565>,
+        {
+            self.windows += 1;
+            let width = area.size.width as usize;
+            for (index, color) in colors.into_iter().enumerate() {
+                let x = area.top_left.x as usize + index % width;
+                let y = area.top_left.y as usize + index / width;
+                self.screen[y][x] = color;
+            }
+            Ok(())
+        }
+    }
+
+    fn mirror() -> MultiDisplay<MockPanel, 2> {
+        MultiDisplay {
+            panels: [MockPanel::new(), MockPanel::new()],
+            delay: EbdHalDelay,
+        }
+    }
+
+    #[test]
+    fn framebuffer_flush_mirrors_to_every_panel() {
+        let mut fb: Framebuffer<WIDTH, HEIGHT> = Framebuffer::new();
+        let area = Rectangle::new(Point::new(1, 2), Size::new(5, 3));
+        fb.fill_solid(&area, Rgb565::RED).unwrap();
+        fb.fill_solid(
+            &Rectangle::new(Point::new(7, 0), Size::new(1, 1)),
+            Rgb565::BLUE,
+        )
+        .unwrap();
+
+        let mut multi = mirror();
+        fb.flush(&mut multi).unwrap();
+
+        for panel in &multi.panels {
+            assert_eq!(panel.screen.as_flattened(), fb.as_slice());
+            assert!(panel.windows > 0);
+        }
+    }
+
+    #[test]
+    fn write_rows_forwards_batches_of_rows() {
+        let mut fb: Framebuffer<WIDTH, HEIGHT> = Framebuffer::new();
+        for y in 0..HEIGHT {
+            let row = Rectangle::new(Point::new(0, y as i32), Size::new(WIDTH as u32, 1));
+            fb.fill_solid(&row, Rgb565::new(0, y as u8, 31)).unwrap();
+        }
+
+        let mut multi = mirror();
+        fb.write_to(&mut multi, &fb.bounds()).unwrap();
+
+        // 默认的write_rows每批开一个窗口：ROW_BATCH行一批，余下的一批
+        for panel in &multi.panels {
+            assert_eq!(panel.screen.as_flattened(), fb.as_slice());
+            assert_eq!(panel.windows, HEIGHT.div_ceil(ROW_BATCH));
+        }
+    }
+
+    #[test]
+    fn shared_or_missing_chip_select_is_rejected() {
+        assert_eq!(check_chip_selects::<MockPanel>(&[None]), Ok(()));
+        assert_eq!(check_chip_selects::<MockPanel>(&[Some(3), Some(4)]), Ok(()));
+        assert_eq!(
+            check_chip_selects::<MockPanel>(&[Some(3), Some(4), Some(3)]),
+            Err(ConfigError::DuplicatePin { pin: 3 })
+        );
+        assert_eq!(
+            check_chip_selects::<MockPanel>(&[Some(3), None]),
+            Err(ConfigError::MissingChipSelect)
+        );
+    }
+}
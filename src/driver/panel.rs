@@ -0,0 +1,359 @@
+use embedded_graphics_core::{draw_target::DrawTarget, pixelcolor::Rgb565, primitives::Rectangle};
+use embedded_hal::delay::DelayNs;
+
+use super::power::PowerState;
+use crate::adapter::delay::EbdHalDelay;
+use crate::adapter::spi::SpiError;
+
+/// 显示旋转角度（顺时针）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    Deg0,
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+impl Rotation {
+    /// 由角度创建，仅接受0/90/180/270
+    pub fn from_degrees(degrees: u16) -> Option<Self> {
+        match degrees {
+            0 => Some(Rotation::Deg0),
+            90 => Some(Rotation::Deg90),
+            180 => Some(Rotation::Deg180),
+            270 => Some(Rotation::Deg270),
+            _ => None,
+        }
+    }
+
+    /// 旋转后宽高是否互换
+    pub fn is_swapped(self) -> bool {
+        matches!(self, Rotation::Deg90 | Rotation::Deg270)
+    }
+
+    /// 对应的MADCTL中MY/MX/MV位
+    pub fn madctl(self) -> u8 {
+        match self {
+            Rotation::Deg0 => 0x00,
+            Rotation::Deg90 => MADCTL_MX | MADCTL_MV,
+            Rotation::Deg180 => MADCTL_MX | MADCTL_MY,
+            Rotation::Deg270 => MADCTL_MY | MADCTL_MV,
+        }
+    }
+}
+
+// MIPI DCS MADCTL位，ST77xx/ILI9341系列通用
+pub(crate) const MADCTL_MY: u8 = 0x80;
+pub(crate) const MADCTL_MX: u8 = 0x40;
+pub(crate) const MADCTL_MV: u8 = 0x20;
+pub(crate) const MADCTL_BGR: u8 = 0x08;
+
+/// 显示方向：旋转 + 镜像
+///
+/// 镜像以旋转后的画面为准：`mirror_x` 左右翻转，`mirror_y` 上下翻转。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Orientation {
+    pub rotation: Rotation,
+    pub mirror_x: bool,
+    pub mirror_y: bool,
+}
+
+impl Orientation {
+    /// 仅旋转，不镜像
+    pub const fn new(rotation: Rotation) -> Self {
+        Self {
+            rotation,
+            mirror_x: false,
+            mirror_y: false,
+        }
+    }
+
+    /// 设置镜像
+    pub const fn mirrored(mut self, mirror_x: bool, mirror_y: bool) -> Self {
+        self.mirror_x = mirror_x;
+        self.mirror_y = mirror_y;
+        self
+    }
+
+    /// 对应的MADCTL中MY/MX/MV位
+    pub fn madctl(self) -> u8 {
+        let mut madctl = self.rotation.madctl();
+
+        // 行列交换后，画面的水平方向由MY控制
+        let (flip_x, flip_y) = if madctl & MADCTL_MV != 0 {
+            (MADCTL_MY, MADCTL_MX)
+        } else {
+            (MADCTL_MX, MADCTL_MY)
+        };
+        if self.mirror_x {
+            madctl ^= flip_x;
+        }
+        if self.mirror_y {
+            madctl ^= flip_y;
+        }
+
+        madctl
+    }
+}
+
+impl Default for Orientation {
+    fn default() -> Self {
+        Self::new(Rotation::Deg0)
+    }
+}
+
+impl From<Rotation> for Orientation {
+    fn from(rotation: Rotation) -> Self {
+        Self::new(rotation)
+    }
+}
+
+/// 显示面板驱动的通用接口
+///
+/// `driver::*`下的驱动都实现此trait，UI代码面向[`DisplayManager`]编写即可。
+pub trait Panel: DrawTarget + Sized {
+    /// 硬件配置
+    type Config: Copy;
+
+    /// 根据配置创建驱动，不发送任何命令
+    fn create(config: Self::Config) -> Result<Self, SpiError>;
+
+    /// 配置中的片选引脚，多块屏共享总线时用来检查冲突
+    fn chip_select(config: &Self::Config) -> Option<u32>;
+
+    /// 运行控制器初始化序列
+    fn init<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), SpiError>;
+
+    /// 当前显示方向
+    fn orientation(&self) -> Orientation;
+
+    /// 切换显示方向，宽高与偏移随之调整
+    fn set_orientation(&mut self, orientation: Orientation) -> Result<(), SpiError>;
+
+    /// 进入（`true`）或退出（`false`）睡眠
+    fn sleep<D: DelayNs>(&mut self, asleep: bool, delay: &mut D) -> Result<(), SpiError> {
+        let state = if asleep {
+            PowerState::Sleep
+        } else {
+            PowerState::On
+        };
+        self.set_power_state(state, delay)
+    }
+
+    /// 当前电源状态
+    fn power_state(&self) -> PowerState;
+
+    /// 切换电源状态，按数据手册插入所需延时
+    fn set_power_state<D: DelayNs>(
+        &mut self,
+        state: PowerState,
+        delay: &mut D,
+    ) -> Result<(), SpiError>;
+
+    /// 将绘制内容送到屏幕，无缓冲的驱动只做帧同步
+    fn flush(&mut self) -> Result<(), SpiError>;
+
+    /// 当前逻辑尺寸 (宽, 高)
+    fn logical_size(&self) -> (u16, u16);
+}
+
+/// 按窗口写入像素流的驱动
+///
+/// 帧缓冲等需要把矩形区域整块送出的代码面向此trait编写：
+/// 驱动只设置一次窗口，随后连续发送像素，不逐点寻址。
+pub trait WindowWrite {
+    type Error;
+
+    /// 设置窗口`area`并按行优先写入`colors`
+    fn write_window<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Rgb565>;
+
+    /// 按行提供像素的写入，每行宽度为`area`的宽度
+    ///
+    /// 默认逐像素转交[`WindowWrite::write_window`]；驱动可覆盖为整行批量转换、
+    /// 大块发送，从PSRAM帧缓冲整屏拷贝时明显更快。
+    fn write_rows<'a, R>(&mut self, area: &Rectangle, rows: R) -> Result<(), Self::Error>
+    where
+        R: IntoIterator<Item = &'a [Rgb565]>,
+    {
+        self.write_window(area, rows.into_iter().flatten().copied())
+    }
+
+    /// 等到可以开始传输下一帧（TE消隐或按帧周期节流），默认不等待
+    fn wait_frame(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<P: Panel + WindowWrite> WindowWrite for DisplayManager<P> {
+    type Error = <P as WindowWrite>::Error;
+
+    fn write_window<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Rgb565>,
+    {
+        self.display.write_window(area, colors)
+    }
+
+    fn write_rows<'a, R>(&mut self, area: &Rectangle, rows: R) -> Result<(), Self::Error>
+    where
+        R: IntoIterator<Item = &'a [Rgb565]>,
+    {
+        self.display.write_rows(area, rows)
+    }
+
+    fn wait_frame(&mut self) -> Result<(), Self::Error> {
+        self.display.wait_frame()
+    }
+}
+
+/// 便捷函数：初始化QSPI并创建任意面板驱动
+///
+/// QSPI只在第一次调用时初始化，多块屏共享总线时可以重复调用。
+pub fn init_panel<P: Panel>(config: P::Config) -> Result<P, SpiError> {
+    // 初始化QSPI（已初始化时不做任何事）
+    ecos_ssc1::qspi::init_qspi(0);
+
+    P::create(config)
+}
+
+/// 便捷函数：使用默认配置初始化任意面板驱动
+pub fn init_default_panel<P>() -> Result<P, SpiError>
+where
+    P: Panel,
+    P::Config: Default,
+{
+    init_panel(P::Config::default())
+}
+
+/// 显示管理器（包含延迟对象）
+pub struct DisplayManager<P: Panel> {
+    pub display: P,
+    pub delay: EbdHalDelay,
+}
+
+impl<P: Panel> DisplayManager<P> {
+    /// 创建显示管理器
+    pub fn new(config: P::Config) -> Result<Self, SpiError> {
+        let display = init_panel(config)?;
+        Ok(Self::from_panel(display))
+    }
+
+    /// 包装已创建的驱动
+    pub fn from_panel(display: P) -> Self {
+        Self {
+            display,
+            delay: EbdHalDelay,
+        }
+    }
+
+    /// 初始化显示驱动
+    pub fn init(&mut self) -> Result<(), SpiError> {
+        self.display.init(&mut self.delay)
+    }
+
+    /// 将绘制内容送到屏幕（无缓冲时只做帧同步）
+    pub fn flush(&mut self) -> Result<(), SpiError> {
+        self.display.flush()
+    }
+
+    /// 当前逻辑尺寸 (宽, 高)
+    pub fn size(&self) -> (u16, u16) {
+        self.display.logical_size()
+    }
+
+    /// 当前显示方向
+    pub fn orientation(&self) -> Orientation {
+        self.display.orientation()
+    }
+
+    /// 运行时切换显示方向，调用后需要重绘画面
+    pub fn set_orientation(&mut self, orientation: Orientation) -> Result<(), SpiError> {
+        self.display.set_orientation(orientation)
+    }
+
+    /// 运行时切换旋转角度（保留镜像设置）
+    pub fn set_rotation(&mut self, rotation: Rotation) -> Result<(), SpiError> {
+        let orientation = Orientation {
+            rotation,
+            ..self.display.orientation()
+        };
+        self.display.set_orientation(orientation)
+    }
+
+    /// 进入（`true`）或退出（`false`）睡眠
+    pub fn sleep(&mut self, asleep: bool) -> Result<(), SpiError> {
+        self.display.sleep(asleep, &mut self.delay)
+    }
+
+    /// 当前电源状态
+    pub fn power_state(&self) -> PowerState {
+        self.display.power_state()
+    }
+
+    /// 切换电源状态
+    pub fn set_power_state(&mut self, state: PowerState) -> Result<(), SpiError> {
+        self.display.set_power_state(state, &mut self.delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotation_sets_scan_bits() {
+        assert_eq!(Orientation::new(Rotation::Deg0).madctl(), 0x00);
+        assert_eq!(
+            Orientation::new(Rotation::Deg90).madctl(),
+            MADCTL_MX | MADCTL_MV
+        );
+        assert_eq!(
+            Orientation::new(Rotation::Deg180).madctl(),
+            MADCTL_MX | MADCTL_MY
+        );
+        assert_eq!(
+            Orientation::new(Rotation::Deg270).madctl(),
+            MADCTL_MY | MADCTL_MV
+        );
+    }
+
+    #[test]
+    fn mirror_follows_rotated_picture() {
+        let portrait = Orientation::new(Rotation::Deg0);
+        assert_eq!(portrait.mirrored(true, false).madctl(), MADCTL_MX);
+        assert_eq!(portrait.mirrored(false, true).madctl(), MADCTL_MY);
+
+        // 行列交换后左右翻转由MY控制
+        let landscape = Orientation::new(Rotation::Deg90);
+        assert_eq!(
+            landscape.mirrored(true, false).madctl(),
+            MADCTL_MX | MADCTL_MY | MADCTL_MV
+        );
+        assert_eq!(landscape.mirrored(false, true).madctl(), MADCTL_MV);
+    }
+
+    #[test]
+    fn mirroring_both_axes_equals_half_turn() {
+        for (rotation, turned) in [
+            (Rotation::Deg0, Rotation::Deg180),
+            (Rotation::Deg90, Rotation::Deg270),
+            (Rotation::Deg180, Rotation::Deg0),
+            (Rotation::Deg270, Rotation::Deg90),
+        ] {
+            assert_eq!(
+                Orientation::new(rotation).mirrored(true, true).madctl(),
+                Orientation::new(turned).madctl()
+            );
+        }
+    }
+
+    #[test]
+    fn swapped_rotations() {
+        assert!(!Rotation::Deg0.is_swapped());
+        assert!(Rotation::Deg90.is_swapped());
+        assert!(!Rotation::Deg180.is_swapped());
+        assert!(Rotation::Deg270.is_swapped());
+    }
+}
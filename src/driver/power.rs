@@ -0,0 +1,253 @@
+use embedded_hal::digital::OutputPin;
+use embedded_hal::pwm::SetDutyCycle;
+
+use super::panel::{DisplayManager, Panel};
+use crate::adapter::gpio::{EbdHalGpio, NoPin};
+use crate::adapter::spi::SpiError;
+
+/// 面板电源状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerState {
+    /// 正常显示（SLPOUT + DISPON + IDMOFF）
+    On,
+    /// 8色空闲模式，功耗更低（SLPOUT + DISPON + IDMON）
+    Idle,
+    /// 关闭显示输出，显存保留，可快速恢复（DISPOFF）
+    Off,
+    /// 睡眠，功耗最低，恢复需要120ms（DISPOFF + SLPIN）
+    Sleep,
+}
+
+/// 背光控制
+pub trait Backlight {
+    /// 设置亮度，0为关闭，255为最亮
+    fn set_brightness(&mut self, level: u8) -> Result<(), SpiError>;
+}
+
+/// GPIO开关背光：亮度非零即点亮，没有中间亮度
+pub struct GpioBacklight {
+    pin: EbdHalGpio,
+    active_high: bool,
+}
+
+impl GpioBacklight {
+    pub fn new(pin: u32, active_high: bool) -> Self {
+        Self {
+            pin: EbdHalGpio::new(pin),
+            active_high,
+        }
+    }
+}
+
+impl Backlight for GpioBacklight {
+    fn set_brightness(&mut self, level: u8) -> Result<(), SpiError> {
+        if (level > 0) == self.active_high {
+            self.pin.set_high()
+        } else {
+            self.pin.set_low()
+        }
+        .map_err(|_| SpiError::GpioError)
+    }
+}
+
+/// PWM调光背光：占空比与亮度成正比
+///
+/// 可用任何实现了[`SetDutyCycle`]的PWM通道；低电平点亮时占空比取反。
+pub struct PwmBacklight<P> {
+    pwm: P,
+    active_high: bool,
+}
+
+impl<P: SetDutyCycle> PwmBacklight<P> {
+    pub fn new(pwm: P, active_high: bool) -> Self {
+        Self { pwm, active_high }
+    }
+
+    /// 取回PWM通道
+    pub fn release(self) -> P {
+        self.pwm
+    }
+}
+
+impl<P: SetDutyCycle> Backlight for PwmBacklight<P> {
+    fn set_brightness(&mut self, level: u8) -> Result<(), SpiError> {
+        let level = if self.active_high { level } else { !level };
+        self.pwm
+            .set_duty_cycle_fraction(level as u16, u8::MAX as u16)
+            .map_err(|_| SpiError::GpioError)
+    }
+}
+
+/// 背光常亮或未接线
+impl Backlight for NoPin {
+    fn set_brightness(&mut self, _level: u8) -> Result<(), SpiError> {
+        Ok(())
+    }
+}
+
+/// 自动调暗配置，时间均以毫秒计
+#[derive(Debug, Clone, Copy)]
+pub struct AutoDimConfig {
+    /// 无操作多久后调暗背光
+    pub dim_after_ms: u32,
+    /// 无操作多久后关闭背光并让面板睡眠
+    pub sleep_after_ms: u32,
+    /// 正常亮度
+    pub brightness: u8,
+    /// 调暗后的亮度
+    pub dim_brightness: u8,
+}
+
+impl Default for AutoDimConfig {
+    fn default() -> Self {
+        Self {
+            dim_after_ms: 15_000,
+            sleep_after_ms: 60_000,
+            brightness: 255,
+            dim_brightness: 32,
+        }
+    }
+}
+
+/// 自动调暗所处阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DimStage {
+    Active,
+    Dimmed,
+    Asleep,
+}
+
+/// 无操作计时器：先调暗背光，再让面板睡眠，有操作时恢复
+///
+/// 时间戳由调用方提供（单调递增的毫秒计数，允许回绕），
+/// 例如在主循环中传入系统tick。
+///
+/// 调暗阶段需要能调光的背光（如[`PwmBacklight`]）；
+/// [`GpioBacklight`]只有开关两档，`dim_brightness`非零时调暗阶段保持全亮。
+pub struct AutoDim<B: Backlight> {
+    backlight: B,
+    config: AutoDimConfig,
+    last_activity: u32,
+    stage: DimStage,
+    /// 睡眠前的电源状态，唤醒时恢复
+    wake_state: PowerState,
+}
+
+impl<B: Backlight> AutoDim<B> {
+    /// 创建计时器并点亮背光
+    pub fn new(mut backlight: B, config: AutoDimConfig, now_ms: u32) -> Result<Self, SpiError> {
+        backlight.set_brightness(config.brightness)?;
+
+        Ok(Self {
+            backlight,
+            config,
+            last_activity: now_ms,
+            stage: DimStage::Active,
+            wake_state: PowerState::On,
+        })
+    }
+
+    /// 当前阶段
+    pub fn stage(&self) -> DimStage {
+        self.stage
+    }
+
+    /// 修改正常亮度，处于正常阶段时立即生效
+    pub fn set_brightness(&mut self, level: u8) -> Result<(), SpiError> {
+        self.config.brightness = level;
+        if self.stage == DimStage::Active {
+            self.backlight.set_brightness(level)?;
+        }
+        Ok(())
+    }
+
+    /// 记录一次用户操作，必要时唤醒面板并恢复亮度
+    pub fn activity<P: Panel>(
+        &mut self,
+        manager: &mut DisplayManager<P>,
+        now_ms: u32,
+    ) -> Result<(), SpiError> {
+        self.last_activity = now_ms;
+
+        match self.stage {
+            DimStage::Active => return Ok(()),
+            DimStage::Dimmed => {}
+            DimStage::Asleep => {
+                // 先恢复面板再点亮背光，避免看到未就绪的画面
+                manager.set_power_state(self.wake_state)?;
+            }
+        }
+
+        self.backlight.set_brightness(self.config.brightness)?;
+        self.stage = DimStage::Active;
+        Ok(())
+    }
+
+    /// 在主循环中周期调用，按无操作时长推进阶段
+    pub fn poll<P: Panel>(
+        &mut self,
+        manager: &mut DisplayManager<P>,
+        now_ms: u32,
+    ) -> Result<DimStage, SpiError> {
+        let idle = now_ms.wrapping_sub(self.last_activity);
+
+        if self.stage != DimStage::Asleep && idle >= self.config.sleep_after_ms {
+            self.wake_state = match manager.power_state() {
+                PowerState::Sleep => PowerState::On,
+                state => state,
+            };
+            self.backlight.set_brightness(0)?;
+            manager.set_power_state(PowerState::Sleep)?;
+            self.stage = DimStage::Asleep;
+        } else if self.stage == DimStage::Active && idle >= self.config.dim_after_ms {
+            self.backlight.set_brightness(self.config.dim_brightness)?;
+            self.stage = DimStage::Dimmed;
+        }
+
+        Ok(self.stage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::convert::Infallible;
+
+    use embedded_hal::pwm::ErrorType;
+
+    use super::*;
+
+    /// 记录占空比的假PWM通道，满量程1000
+    struct MockPwm {
+        duty: u16,
+    }
+
+    impl ErrorType for MockPwm {
+        type Error = Infallible;
+    }
+
+    impl SetDutyCycle for MockPwm {
+        fn max_duty_cycle(&self) -> u16 {
+            1000
+        }
+
+        fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Infallible> {
+            self.duty = duty;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn pwm_backlight_scales_duty_cycle() {
+        let mut backlight = PwmBacklight::new(MockPwm { duty: 0 }, true);
+        for (level, duty) in [(0, 0), (32, 125), (128, 501), (255, 1000)] {
+            backlight.set_brightness(level).unwrap();
+            assert_eq!(backlight.pwm.duty, duty, "level {level}");
+        }
+
+        let mut backlight = PwmBacklight::new(MockPwm { duty: 0 }, false);
+        backlight.set_brightness(255).unwrap();
+        assert_eq!(backlight.pwm.duty, 0);
+        backlight.set_brightness(0).unwrap();
+        assert_eq!(backlight.release().duty, 1000);
+    }
+}
@@ -0,0 +1,92 @@
+use embedded_hal::delay::DelayNs;
+
+use super::st7735::CommandPort;
+use super::st7735::cmd::Command;
+use crate::adapter::spi::SpiError;
+
+/// 可识别的控制器
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Controller {
+    /// ST7735 / ST7735R / ST7735S
+    St7735,
+    St7789,
+    Ili9341,
+}
+
+/// 回读到的控制器标识
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PanelId {
+    /// 识别出的控制器，未知标识时为`None`
+    pub controller: Option<Controller>,
+    /// 厂商 / 版本 / 模组三字节标识
+    pub id: [u8; 3],
+}
+
+impl PanelId {
+    fn from_id(id: [u8; 3]) -> Self {
+        let controller = match id {
+            [0x7C, _, _] => Some(Controller::St7735),
+            [0x85, 0x85, _] => Some(Controller::St7789),
+            [_, 0x93, 0x41] => Some(Controller::Ili9341),
+            _ => None,
+        };
+        Self { controller, id }
+    }
+
+    /// 版本字节，用于区分同一控制器的不同批次
+    pub fn version(&self) -> u8 {
+        self.id[1]
+    }
+}
+
+/// ILI9341的RDID4，不属于ST7735命令集
+const CMD_RDID4: u8 = 0xD3;
+
+/// 读回控制器标识（RDDID，不成功时依次尝试RDID1-3与ILI9341的RDID4）
+///
+/// 先复位控制器以保证处于已知状态，QSPI需已初始化。
+/// 未接回读线时读到的全是0x00或0xFF，此时返回`Ok(None)`。
+pub fn probe<D: DelayNs>(
+    dc_pin: u32,
+    cs_pin: Option<u32>,
+    rst_pin: Option<u32>,
+    delay: &mut D,
+) -> Result<Option<PanelId>, SpiError> {
+    let mut port = CommandPort::new(Some(dc_pin), cs_pin)?;
+    port.reset(rst_pin, delay)?;
+
+    // RDDID在数据前有1个哑时钟，按32位读回后左移1位
+    let mut raw = [0u8; 4];
+    port.read_command(Command::RddId, &mut raw)?;
+    let [id1, id2, id3, _] = (u32::from_be_bytes(raw) << 1).to_be_bytes();
+    if let Some(id) = valid([id1, id2, id3]) {
+        return Ok(Some(PanelId::from_id(id)));
+    }
+
+    let mut id = [0u8; 3];
+    for (byte, command) in id
+        .iter_mut()
+        .zip([Command::RdId1, Command::RdId2, Command::RdId3])
+    {
+        let mut buffer = [0u8; 1];
+        port.read_command(command, &mut buffer)?;
+        *byte = buffer[0];
+    }
+    if let Some(id) = valid(id) {
+        return Ok(Some(PanelId::from_id(id)));
+    }
+
+    // ILI9341：首字节为哑字节
+    let mut raw = [0u8; 4];
+    port.read_command(CMD_RDID4, &mut raw)?;
+    Ok(valid([raw[1], raw[2], raw[3]]).map(PanelId::from_id))
+}
+
+/// 全0或全1视为没有回读
+fn valid(id: [u8; 3]) -> Option<[u8; 3]> {
+    if id.iter().all(|&b| b == 0x00) || id.iter().all(|&b| b == 0xFF) {
+        None
+    } else {
+        Some(id)
+    }
+}
@@ -0,0 +1,300 @@
+use embedded_graphics_core::{
+    Pixel,
+    draw_target::DrawTarget,
+    geometry::{Dimensions, Point, Size},
+    primitives::Rectangle,
+};
+
+/// 垂直滚动区域，按0°方向的扫描行计（不含显存偏移）
+///
+/// `top + height + bottom` 必须等于面板0°方向的高度。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScrollArea {
+    /// 顶部固定区行数
+    pub top: u16,
+    /// 滚动区行数
+    pub height: u16,
+    /// 底部固定区行数
+    pub bottom: u16,
+}
+
+/// 滚动状态：记录当前滚动量，并把“看到的行”换算为“应写入的行”
+///
+/// 硬件滚动只改变扫描起点，显存内容不动；
+/// 想让内容出现在屏幕第`y`行，需要写到[`VerticalScroller::map_row`]给出的行。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerticalScroller {
+    area: ScrollArea,
+    offset: u16,
+    /// 当前方向是否上下翻转（MY），翻转时逻辑行与扫描行方向相反
+    flipped: bool,
+}
+
+impl VerticalScroller {
+    pub(crate) fn new(area: ScrollArea, flipped: bool) -> Self {
+        Self {
+            area,
+            offset: 0,
+            flipped,
+        }
+    }
+
+    /// 滚动区域
+    pub fn area(&self) -> ScrollArea {
+        self.area
+    }
+
+    /// 当前滚动量（0..height）
+    pub fn offset(&self) -> u16 {
+        self.offset
+    }
+
+    pub(crate) fn set_offset(&mut self, offset: u16) {
+        self.offset = offset % self.area.height.max(1);
+    }
+
+    /// 滚动区在逻辑坐标中的起始行
+    fn logical_start(&self) -> u16 {
+        if self.flipped {
+            self.area.bottom
+        } else {
+            self.area.top
+        }
+    }
+
+    /// 逻辑坐标下的滚动量
+    fn logical_shift(&self) -> u16 {
+        if self.flipped {
+            (self.area.height - self.offset) % self.area.height
+        } else {
+            self.offset
+        }
+    }
+
+    /// 屏幕上第`row`行（逻辑坐标）对应的绘制行，固定区原样返回
+    pub fn map_row(&self, row: u16) -> u16 {
+        let start = self.logical_start();
+        let height = self.area.height;
+        if height == 0 || row < start || row >= start + height {
+            return row;
+        }
+        start + (row - start + self.logical_shift()) % height
+    }
+
+    /// 换算一个点
+    pub fn map_point(&self, point: Point) -> Point {
+        if point.y < 0 || point.y > u16::MAX as i32 {
+            return point;
+        }
+        Point::new(point.x, self.map_row(point.y as u16) as i32)
+    }
+
+    /// 从`row`起连续映射的行数（到滚动区回绕点或区域边界为止）
+    fn run_len(&self, row: u16) -> u16 {
+        let start = self.logical_start();
+        let end = start + self.area.height;
+        if row < start {
+            start - row
+        } else if row >= end {
+            u16::MAX
+        } else {
+            let mapped = self.map_row(row);
+            (end - mapped).min(end - row)
+        }
+    }
+}
+
+/// 滚动视图：按滚动状态换算坐标后再绘制到目标上
+///
+/// 在视图上按“屏幕上看到的位置”绘制即可，回绕由视图处理。
+pub struct ScrollView<'a, D> {
+    target: &'a mut D,
+    scroller: VerticalScroller,
+}
+
+impl<'a, D: DrawTarget> ScrollView<'a, D> {
+    pub fn new(target: &'a mut D, scroller: VerticalScroller) -> Self {
+        Self { target, scroller }
+    }
+}
+
+impl<D: DrawTarget> Dimensions for ScrollView<'_, D> {
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+impl<D: DrawTarget> DrawTarget for ScrollView<'_, D> {
+    type Color = D::Color;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let scroller = self.scroller;
+        self.target.draw_iter(
+            pixels
+                .into_iter()
+                .map(|Pixel(point, color)| Pixel(scroller.map_point(point), color)),
+        )
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.target.bounding_box());
+        if area.size.width == 0 || area.size.height == 0 || area.top_left.y < 0 {
+            return Ok(());
+        }
+
+        // 按行拆成映射后仍然连续的若干段
+        let mut row = area.top_left.y as u16;
+        let mut remaining = area.size.height;
+        while remaining > 0 {
+            let run = (self.scroller.run_len(row) as u32).min(remaining);
+            let top_left = Point::new(area.top_left.x, self.scroller.map_row(row) as i32);
+            let piece = Rectangle::new(top_left, Size::new(area.size.width, run));
+            self.target.fill_solid(&piece, color)?;
+
+            row += run as u16;
+            remaining -= run;
+        }
+
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.target.clear(color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: ScrollArea = ScrollArea {
+        top: 2,
+        height: 5,
+        bottom: 3,
+    };
+
+    fn scrolled(area: ScrollArea, flipped: bool, offset: u16) -> VerticalScroller {
+        let mut scroller = VerticalScroller::new(area, flipped);
+        scroller.set_offset(offset);
+        scroller
+    }
+
+    /// 硬件行为：扫描行`row`显示的显存行
+    fn scanned(area: ScrollArea, offset: u16, row: u16) -> u16 {
+        if row < area.top || row >= area.top + area.height {
+            row
+        } else {
+            area.top + (row - area.top + offset) % area.height
+        }
+    }
+
+    #[test]
+    fn map_row_fixed_and_wrapping_rows() {
+        let scroller = scrolled(AREA, false, 3);
+        let mapped: [u16; 10] = core::array::from_fn(|row| scroller.map_row(row as u16));
+        assert_eq!(mapped, [0, 1, 5, 6, 2, 3, 4, 7, 8, 9]);
+
+        // 翻转时底部固定区在逻辑坐标上方
+        let scroller = scrolled(AREA, true, 3);
+        let mapped: [u16; 10] = core::array::from_fn(|row| scroller.map_row(row as u16));
+        assert_eq!(mapped, [0, 1, 2, 5, 6, 7, 3, 4, 8, 9]);
+    }
+
+    #[test]
+    fn map_row_matches_hardware_scan() {
+        let areas = [
+            AREA,
+            ScrollArea {
+                top: 0,
+                height: 10,
+                bottom: 0,
+            },
+            ScrollArea {
+                top: 0,
+                height: 7,
+                bottom: 3,
+            },
+            ScrollArea {
+                top: 4,
+                height: 6,
+                bottom: 0,
+            },
+            ScrollArea {
+                top: 3,
+                height: 1,
+                bottom: 6,
+            },
+        ];
+        for area in areas {
+            let rows = area.top + area.height + area.bottom;
+            for offset in 0..area.height * 2 {
+                for flipped in [false, true] {
+                    let scroller = scrolled(area, flipped, offset);
+                    let offset = offset % area.height;
+                    assert_eq!(scroller.offset(), offset);
+                    for row in 0..rows {
+                        // 显示在逻辑第`row`行的内容所在的显存行
+                        let expected = if flipped {
+                            rows - 1 - scanned(area, offset, rows - 1 - row)
+                        } else {
+                            scanned(area, offset, row)
+                        };
+                        assert_eq!(
+                            scroller.map_row(row),
+                            expected,
+                            "{area:?} offset {offset} flipped {flipped} row {row}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn run_len_stops_at_wrap_and_boundaries() {
+        let scroller = scrolled(AREA, false, 3);
+        assert_eq!(scroller.run_len(0), 2);
+        assert_eq!(scroller.run_len(1), 1);
+        assert_eq!(scroller.run_len(2), 2);
+        assert_eq!(scroller.run_len(3), 1);
+        assert_eq!(scroller.run_len(4), 3);
+        assert_eq!(scroller.run_len(6), 1);
+        assert_eq!(scroller.run_len(7), u16::MAX);
+
+        for offset in 0..AREA.height {
+            for flipped in [false, true] {
+                let scroller = scrolled(AREA, flipped, offset);
+                let start = scroller.logical_start();
+                let end = start + AREA.height;
+                for row in start..end {
+                    let len = scroller.run_len(row);
+                    assert!(len > 0 && row + len <= end);
+                    let mapped = scroller.map_row(row);
+                    for k in 0..len {
+                        assert_eq!(scroller.map_row(row + k), mapped + k);
+                    }
+                    // 之后要么出了滚动区，要么回绕到滚动区开头
+                    assert!(row + len == end || scroller.map_row(row + len) == start);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn empty_scroll_area_maps_identity() {
+        let area = ScrollArea {
+            top: 4,
+            height: 0,
+            bottom: 6,
+        };
+        let scroller = scrolled(area, false, 3);
+        assert_eq!(scroller.offset(), 0);
+        for row in 0..10 {
+            assert_eq!(scroller.map_row(row), row);
+        }
+    }
+}
@@ -0,0 +1,387 @@
+use core::ops::BitOr;
+
+use embedded_graphics_core::{
+    pixelcolor::{IntoStorage, Rgb565},
+    primitives::Rectangle,
+};
+use embedded_hal::digital::OutputPin;
+use embedded_hal::spi::{Operation, SpiDevice};
+
+use crate::adapter::gpio::EbdHalGpio;
+use crate::adapter::pixel::ColorMode;
+use crate::adapter::spi::{BURST_LEN, EbdHalSpiDevice, SpiError};
+use crate::driver::panel::WindowWrite;
+
+/// ST7735系列命令
+///
+/// 未列出的厂商命令可直接以`u8`发送，[`RawPanel`]两者都接受。
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Nop = 0x00,
+    /// 软件复位，之后需等待120ms
+    SwReset = 0x01,
+    /// 读控制器标识（含1个哑时钟）
+    RddId = 0x04,
+    /// 进入睡眠
+    SlpIn = 0x10,
+    /// 退出睡眠，之后需等待120ms
+    SlpOut = 0x11,
+    /// 部分显示模式
+    PtlOn = 0x12,
+    /// 普通显示模式
+    NorOn = 0x13,
+    InvOff = 0x20,
+    InvOn = 0x21,
+    /// 选择预置伽马曲线
+    GamSet = 0x26,
+    DispOff = 0x28,
+    DispOn = 0x29,
+    /// 列地址范围，见[`Caset`]
+    Caset = 0x2A,
+    /// 行地址范围，见[`Raset`]
+    Raset = 0x2B,
+    /// 写显存
+    RamWr = 0x2C,
+    /// 读显存
+    RamRd = 0x2E,
+    /// 部分显示区域
+    PtlAr = 0x30,
+    /// 垂直滚动区域
+    VscrDef = 0x33,
+    TeOff = 0x34,
+    TeOn = 0x35,
+    /// 扫描方向与颜色顺序，见[`Madctl`]
+    Madctl = 0x36,
+    /// 垂直滚动起始地址
+    VscSad = 0x37,
+    IdmOff = 0x38,
+    IdmOn = 0x39,
+    /// 接口像素格式，见[`Colmod`]
+    Colmod = 0x3A,
+    FrmCtr1 = 0xB1,
+    FrmCtr2 = 0xB2,
+    FrmCtr3 = 0xB3,
+    /// 反转控制
+    InvCtr = 0xB4,
+    PwCtr1 = 0xC0,
+    PwCtr2 = 0xC1,
+    PwCtr3 = 0xC2,
+    PwCtr4 = 0xC3,
+    PwCtr5 = 0xC4,
+    VmCtr1 = 0xC5,
+    RdId1 = 0xDA,
+    RdId2 = 0xDB,
+    RdId3 = 0xDC,
+    GmCtrP1 = 0xE0,
+    GmCtrN1 = 0xE1,
+}
+
+impl Command {
+    /// 命令字节
+    pub const fn code(self) -> u8 {
+        self as u8
+    }
+}
+
+impl From<Command> for u8 {
+    fn from(command: Command) -> Self {
+        command.code()
+    }
+}
+
+/// 带参数的命令
+pub trait CommandParams {
+    /// 对应的命令
+    const COMMAND: Command;
+    /// 参数字节
+    type Bytes: AsRef<[u8]>;
+
+    fn params(&self) -> Self::Bytes;
+}
+
+/// 列地址范围（显存坐标，含端点）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Caset {
+    pub start: u16,
+    pub end: u16,
+}
+
+impl Caset {
+    pub const fn new(start: u16, end: u16) -> Self {
+        Self { start, end }
+    }
+}
+
+impl CommandParams for Caset {
+    const COMMAND: Command = Command::Caset;
+    type Bytes = [u8; 4];
+
+    fn params(&self) -> [u8; 4] {
+        address_range(self.start, self.end)
+    }
+}
+
+/// 行地址范围（显存坐标，含端点）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Raset {
+    pub start: u16,
+    pub end: u16,
+}
+
+impl Raset {
+    pub const fn new(start: u16, end: u16) -> Self {
+        Self { start, end }
+    }
+}
+
+impl CommandParams for Raset {
+    const COMMAND: Command = Command::Raset;
+    type Bytes = [u8; 4];
+
+    fn params(&self) -> [u8; 4] {
+        address_range(self.start, self.end)
+    }
+}
+
+fn address_range(start: u16, end: u16) -> [u8; 4] {
+    let [start_hi, start_lo] = start.to_be_bytes();
+    let [end_hi, end_lo] = end.to_be_bytes();
+    [start_hi, start_lo, end_hi, end_lo]
+}
+
+/// MADCTL位组合，可用`|`连接
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Madctl(u8);
+
+impl Madctl {
+    /// 行地址倒序（上下镜像）
+    pub const MY: Self = Self(0x80);
+    /// 列地址倒序（左右镜像）
+    pub const MX: Self = Self(0x40);
+    /// 行列交换
+    pub const MV: Self = Self(0x20);
+    /// 垂直刷新顺序
+    pub const ML: Self = Self(0x10);
+    /// BGR颜色顺序
+    pub const BGR: Self = Self(0x08);
+    /// 水平刷新顺序
+    pub const MH: Self = Self(0x04);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits)
+    }
+
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl BitOr for Madctl {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(rhs)
+    }
+}
+
+impl CommandParams for Madctl {
+    const COMMAND: Command = Command::Madctl;
+    type Bytes = [u8; 1];
+
+    fn params(&self) -> [u8; 1] {
+        [self.0]
+    }
+}
+
+/// 接口像素格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Colmod(pub ColorMode);
+
+impl From<ColorMode> for Colmod {
+    fn from(mode: ColorMode) -> Self {
+        Self(mode)
+    }
+}
+
+impl CommandParams for Colmod {
+    const COMMAND: Command = Command::Colmod;
+    type Bytes = [u8; 1];
+
+    fn params(&self) -> [u8; 1] {
+        [self.0.colmod()]
+    }
+}
+
+/// 最底层的4线面板接口：SPI设备 + DC引脚
+///
+/// 只负责按命令/数据切换DC并发送，不做初始化、不记录状态，
+/// 适合点亮新模组或调试时逐条发送命令。
+pub struct RawPanel {
+    spi: EbdHalSpiDevice,
+    dc: EbdHalGpio,
+}
+
+impl RawPanel {
+    pub fn new(spi: EbdHalSpiDevice, dc: EbdHalGpio) -> Self {
+        Self { spi, dc }
+    }
+
+    /// 按引脚创建，QSPI需已初始化
+    pub fn with_pins(dc_pin: u32, cs_pin: Option<u32>) -> Result<Self, SpiError> {
+        let spi = EbdHalSpiDevice::with_optional_cs(cs_pin).ok_or(SpiError::NotInitialized)?;
+        Ok(Self::new(spi, EbdHalGpio::new(dc_pin)))
+    }
+
+    /// 发送命令及其参数，`command`可以是[`Command`]或厂商命令字节
+    pub fn command(&mut self, command: impl Into<u8>, params: &[u8]) -> Result<(), SpiError> {
+        self.begin(command)?;
+        if params.is_empty() {
+            Ok(())
+        } else {
+            self.spi.write(params)
+        }
+    }
+
+    /// 发送带类型参数的命令
+    pub fn send<P: CommandParams>(&mut self, params: P) -> Result<(), SpiError> {
+        self.command(P::COMMAND, params.params().as_ref())
+    }
+
+    /// 只发送命令字节，随后DC保持为数据，可用[`RawPanel::write_data`]分批发送参数或像素
+    pub fn begin(&mut self, command: impl Into<u8>) -> Result<(), SpiError> {
+        self.dc.set_low().map_err(|_| SpiError::GpioError)?;
+        self.spi.write(&[command.into()])?;
+        self.dc.set_high().map_err(|_| SpiError::GpioError)
+    }
+
+    /// 发送数据
+    pub fn write_data(&mut self, data: &[u8]) -> Result<(), SpiError> {
+        self.dc.set_high().map_err(|_| SpiError::GpioError)?;
+        self.spi.write(data)
+    }
+
+    /// 设置写入窗口（显存坐标，含端点）
+    pub fn set_window(&mut self, x0: u16, y0: u16, x1: u16, y1: u16) -> Result<(), SpiError> {
+        self.send(Caset::new(x0, x1))?;
+        self.send(Raset::new(y0, y1))
+    }
+
+    /// 开始写显存，随后用[`RawPanel::write_data`]发送像素
+    pub fn begin_write(&mut self) -> Result<(), SpiError> {
+        self.begin(Command::RamWr)
+    }
+
+    /// 发送读命令并读回`buffer.len()`字节
+    ///
+    /// 片选拉高会中止读操作，命令与读回在同一次事务内完成。
+    pub fn read(&mut self, command: impl Into<u8>, buffer: &mut [u8]) -> Result<(), SpiError> {
+        self.dc.set_low().map_err(|_| SpiError::GpioError)?;
+        self.spi
+            .transaction(&mut [Operation::Write(&[command.into()]), Operation::Read(buffer)])
+    }
+
+    /// 拆回SPI设备与DC引脚
+    pub fn release(self) -> (EbdHalSpiDevice, EbdHalGpio) {
+        (self.spi, self.dc)
+    }
+}
+
+/// 显存坐标（不加偏移、不裁剪），RGB565
+impl WindowWrite for RawPanel {
+    type Error = SpiError;
+
+    fn write_window<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Rgb565>,
+    {
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+        self.set_window(
+            area.top_left.x as u16,
+            area.top_left.y as u16,
+            bottom_right.x as u16,
+            bottom_right.y as u16,
+        )?;
+        self.begin_write()?;
+
+        let count = (area.size.width * area.size.height) as usize;
+        let mut burst = [0u8; BURST_LEN];
+        let mut len = 0;
+        for color in colors.into_iter().take(count) {
+            burst[len..len + 2].copy_from_slice(&color.into_storage().to_be_bytes());
+            len += 2;
+            if len == BURST_LEN {
+                self.spi.write(&burst)?;
+                len = 0;
+            }
+        }
+        if len > 0 {
+            self.spi.write(&burst[..len])?;
+        }
+        Ok(())
+    }
+
+    fn write_rows<'a, R>(&mut self, area: &Rectangle, rows: R) -> Result<(), Self::Error>
+    where
+        R: IntoIterator<Item = &'a [Rgb565]>,
+    {
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+        self.set_window(
+            area.top_left.x as u16,
+            area.top_left.y as u16,
+            bottom_right.x as u16,
+            bottom_right.y as u16,
+        )?;
+        self.begin_write()?;
+        stream_rows(area, rows, |bytes| self.spi.write(bytes))
+    }
+}
+
+/// 把按行提供的像素转成大端RGB565，攒满一块后交给`write`
+///
+/// 每行只取`area`的宽度，最多取`area`的高度行。
+pub(crate) fn stream_rows<'a, R, F>(area: &Rectangle, rows: R, mut write: F) -> Result<(), SpiError>
+where
+    R: IntoIterator<Item = &'a [Rgb565]>,
+    F: FnMut(&[u8]) -> Result<(), SpiError>,
+{
+    let width = area.size.width as usize;
+    let mut burst = [0u8; BURST_LEN];
+    let mut len = 0;
+
+    for row in rows.into_iter().take(area.size.height as usize) {
+        let mut row = &row[..width.min(row.len())];
+        while !row.is_empty() {
+            let (chunk, rest) = row.split_at(row.len().min((BURST_LEN - len) / 2));
+            for (bytes, color) in burst[len..].chunks_exact_mut(2).zip(chunk) {
+                bytes.copy_from_slice(&color.into_storage().to_be_bytes());
+            }
+            len += chunk.len() * 2;
+            if len == BURST_LEN {
+                write(&burst)?;
+                len = 0;
+            }
+            row = rest;
+        }
+    }
+    if len > 0 {
+        write(&burst[..len])?;
+    }
+    Ok(())
+}
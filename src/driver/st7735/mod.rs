@@ -0,0 +1,1827 @@
+pub mod cmd;
+mod readback;
+pub mod script;
+
+use core::marker::Copy;
+use core::prelude::rust_2024::derive;
+use core::{
+    default::Default,
+    option::Option,
+    option::Option::{None, Some},
+    result::Result,
+    result::Result::Ok,
+};
+
+use core::clone::Clone;
+use core::fmt::Debug;
+use core::marker::PhantomData;
+
+use embedded_graphics_core::{
+    Pixel,
+    draw_target::DrawTarget,
+    geometry::{Dimensions, OriginDimensions, Point, Size},
+    pixelcolor::{PixelColor, Rgb565, Rgb888},
+    primitives::{PointsIter, Rectangle},
+};
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+use heapless::Vec;
+
+use self::cmd::{Caset, Colmod, Command, CommandParams, Madctl, Raset, RawPanel, stream_rows};
+pub use self::script::{EncodedScript, InitScript, InitStep};
+use super::panel::{
+    DisplayManager, MADCTL_BGR, MADCTL_MV, MADCTL_MX, MADCTL_MY, Orientation, Panel as PanelDriver,
+    Rotation, WindowWrite, init_default_panel, init_panel,
+};
+use super::power::PowerState;
+use super::probe::{Controller, probe};
+use super::scroll::{ScrollArea, ScrollView, VerticalScroller};
+use super::tuning::{FrameRates, Gamma, PowerControl};
+use super::vsync::{FrameControl, FrameSync, FrameSyncer};
+use crate::adapter::delay::EbdHalDelay;
+use crate::adapter::gpio::{EbdHalGpio, OptionalPin};
+use crate::adapter::pixel::{ColorMode, PixelPacker};
+use crate::adapter::spi::{BURST_LEN, ConfigError, EbdHalSpiDevice, SpiError};
+use crate::adapter::three_wire::ThreeWireSpi;
+
+#[cfg(feature = "st7735-lcd")]
+use st7735_lcd::ST7735;
+
+#[cfg(feature = "st7735-lcd-doublebuffering")]
+use st7735_lcd_doublebuffering::{Orientation as BufferedOrientation, ST7735Buffered};
+
+#[cfg(not(any(feature = "st7735-lcd", feature = "st7735-lcd-doublebuffering")))]
+compile_error!("至少需要启用 `st7735-lcd` 或 `st7735-lcd-doublebuffering` 之一");
+
+/// 直接模式后端
+#[cfg(feature = "st7735-lcd")]
+type DirectBackend = ST7735<EbdHalSpiDevice, OptionalPin, OptionalPin>;
+
+/// 缓冲模式后端
+#[cfg(feature = "st7735-lcd-doublebuffering")]
+type BufferedBackend = ST7735Buffered<EbdHalSpiDevice, EbdHalGpio>;
+
+/// SPI接线方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpiInterface {
+    /// 4线：SCL/SDA/CS + DC引脚
+    #[default]
+    FourWire,
+    /// 3线：D/C作为第9位随数据发送，不占用DC引脚（仅直接模式）
+    ThreeWire,
+}
+
+/// 刷新模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayMode {
+    /// 绘制即写入屏幕，`flush()`为空操作（需要`st7735-lcd`特性）
+    Direct,
+    /// 绘制写入缓冲区，`flush()`时交换到屏幕（需要`st7735-lcd-doublebuffering`特性）
+    Buffered,
+}
+
+impl DisplayMode {
+    /// 对应的后端是否已编译进来
+    pub fn is_available(self) -> bool {
+        match self {
+            DisplayMode::Direct => cfg!(feature = "st7735-lcd"),
+            DisplayMode::Buffered => cfg!(feature = "st7735-lcd-doublebuffering"),
+        }
+    }
+}
+
+impl Default for DisplayMode {
+    fn default() -> Self {
+        if cfg!(feature = "st7735-lcd") {
+            DisplayMode::Direct
+        } else {
+            DisplayMode::Buffered
+        }
+    }
+}
+
+enum Backend {
+    #[cfg(feature = "st7735-lcd")]
+    Direct(DirectBackend),
+    #[cfg(feature = "st7735-lcd-doublebuffering")]
+    Buffered(BufferedBackend),
+}
+
+/// 对当前后端执行同一段代码
+macro_rules! with_backend {
+    ($backend:expr, $display:ident => $body:expr) => {
+        match $backend {
+            #[cfg(feature = "st7735-lcd")]
+            Backend::Direct($display) => $body,
+            #[cfg(feature = "st7735-lcd-doublebuffering")]
+            Backend::Buffered($display) => $body,
+        }
+    };
+}
+
+/// ST7735显示类型
+///
+/// 直接模式与缓冲模式共用一套接口，两个特性可同时启用，
+/// 由配置中的[`DisplayMode`]在运行时选择。
+pub struct St7735Display {
+    backend: Backend,
+    port: CommandPort,
+    config: St7735Config,
+    power: PowerState,
+    scroll: Option<VerticalScroller>,
+    sync: FrameSyncer,
+    /// 当前方向下的显存偏移
+    offset: (u16, u16),
+}
+
+impl St7735Display {
+    /// 当前硬件配置
+    pub fn config(&self) -> &St7735Config {
+        &self.config
+    }
+
+    /// 当前刷新模式
+    pub fn mode(&self) -> DisplayMode {
+        match self.backend {
+            #[cfg(feature = "st7735-lcd")]
+            Backend::Direct(_) => DisplayMode::Direct,
+            #[cfg(feature = "st7735-lcd-doublebuffering")]
+            Backend::Buffered(_) => DisplayMode::Buffered,
+        }
+    }
+
+    /// 运行控制器初始化序列，随后写入方向与偏移
+    ///
+    /// 先复位一次：接了RST时拉低复位，否则发送SWRESET。
+    /// 直接模式后端的序列自带硬复位与SWRESET，不再另行复位。
+    pub fn init<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), SpiError> {
+        // 后端只会用DC引脚发命令，3线或自定义脚本时由命令通道发送
+        if self.config.interface == SpiInterface::ThreeWire || self.config.init_script.is_some() {
+            self.reset(delay)?;
+            return self.init_through_port(delay);
+        }
+
+        match &mut self.backend {
+            #[cfg(feature = "st7735-lcd")]
+            Backend::Direct(display) => display.init(delay),
+            // 方向随后由MADCTL统一写入
+            #[cfg(feature = "st7735-lcd-doublebuffering")]
+            Backend::Buffered(display) => {
+                self.port.reset(self.config.rst_pin, delay)?;
+                display.init(delay, &BufferedOrientation::Portrait)
+            }
+        }
+        .map_err(|_| SpiError::TransferFailed)?;
+        self.power = PowerState::On;
+        // 后端初始化时绕过了窗口缓存
+        self.port.invalidate_window();
+
+        self.finish_init()
+    }
+
+    /// 经命令通道发送初始化序列：配置中的脚本，或内置序列加反色与DISPON
+    fn init_through_port<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), SpiError> {
+        if let Some(script) = self.config.init_script {
+            self.run_script(script, delay)?;
+        } else {
+            self.run_script(InitScript::ST7735R, delay)?;
+            let inversion = if self.config.inverted {
+                Command::InvOn
+            } else {
+                Command::InvOff
+            };
+            self.port.write_command(inversion, &[])?;
+            self.port.write_command(Command::DispOn, &[])?;
+            delay.delay_ms(120);
+        }
+
+        self.power = PowerState::On;
+        self.finish_init()
+    }
+
+    /// 逐条发送初始化脚本
+    pub fn run_script<D: DelayNs>(
+        &mut self,
+        script: InitScript<'_>,
+        delay: &mut D,
+    ) -> Result<(), SpiError> {
+        for step in script.steps() {
+            self.port.write_command(step.command, step.params)?;
+            if step.delay_ms > 0 {
+                delay.delay_ms(step.delay_ms);
+            }
+        }
+        Ok(())
+    }
+
+    /// 控制器序列之后的公共步骤：TE、颜色模式、调校参数与方向
+    fn finish_init(&mut self) -> Result<(), SpiError> {
+        // TE只输出垂直消隐（模式0）
+        if let FrameSync::Te(pin) = self.config.frame_sync {
+            EbdHalGpio::input(pin);
+            self.port.write_command(Command::TeOn, &[0x00])?;
+        }
+
+        // 覆盖后端初始化序列中的默认值
+        if self.config.color_mode != ColorMode::Rgb565 {
+            self.port.send(Colmod(self.config.color_mode))?;
+        }
+        if let Some(rates) = self.config.frame_rates {
+            self.write_frame_rates(rates)?;
+        }
+        if let Some(power) = self.config.power_control {
+            self.write_power_control(power)?;
+        }
+        if let Some(gamma) = self.config.gamma {
+            self.write_gamma(gamma)?;
+        }
+
+        self.apply_orientation()
+    }
+
+    /// 运行时替换伽马表，立即生效
+    pub fn set_gamma(&mut self, gamma: Gamma) -> Result<(), SpiError> {
+        self.write_gamma(gamma)?;
+        self.config.gamma = Some(gamma);
+        Ok(())
+    }
+
+    /// 运行时修改帧率，帧同步的节拍随之调整
+    pub fn set_frame_rates(&mut self, rates: FrameRates) -> Result<(), SpiError> {
+        self.write_frame_rates(rates)?;
+        self.config.frame_rates = Some(rates);
+        self.sync = FrameSyncer::new(self.config.frame_sync, self.config.frame_period_us());
+        Ok(())
+    }
+
+    /// 运行时修改电源控制寄存器
+    pub fn set_power_control(&mut self, power: PowerControl) -> Result<(), SpiError> {
+        self.write_power_control(power)?;
+        self.config.power_control = Some(power);
+        Ok(())
+    }
+
+    fn write_gamma(&mut self, gamma: Gamma) -> Result<(), SpiError> {
+        self.port.write_command(Command::GmCtrP1, &gamma.positive)?;
+        self.port.write_command(Command::GmCtrN1, &gamma.negative)
+    }
+
+    fn write_frame_rates(&mut self, rates: FrameRates) -> Result<(), SpiError> {
+        let [dot, column] = rates.partial.map(FrameControl::params);
+        self.port
+            .write_command(Command::FrmCtr1, &rates.normal.params())?;
+        self.port
+            .write_command(Command::FrmCtr2, &rates.idle.params())?;
+        self.port.write_command(
+            Command::FrmCtr3,
+            &[dot[0], dot[1], dot[2], column[0], column[1], column[2]],
+        )
+    }
+
+    fn write_power_control(&mut self, power: PowerControl) -> Result<(), SpiError> {
+        self.port.write_command(Command::PwCtr1, &power.pwctr1)?;
+        self.port.write_command(Command::PwCtr2, &[power.pwctr2])?;
+        self.port.write_command(Command::PwCtr3, &power.pwctr3)?;
+        self.port.write_command(Command::PwCtr4, &power.pwctr4)?;
+        self.port.write_command(Command::PwCtr5, &power.pwctr5)?;
+        self.port.write_command(Command::VmCtr1, &[power.vmctr1])
+    }
+
+    /// 运行时切换显示方向
+    ///
+    /// 宽高与偏移随方向自动调整；后端会按新尺寸重建，调用后需要重绘画面。
+    /// 电源状态与帧同步保持不变，滚动区按扫描方向定义，切换前会先退出滚动。
+    pub fn set_orientation(&mut self, orientation: Orientation) -> Result<(), SpiError> {
+        if self.scroll.is_some() {
+            self.stop_scroll()?;
+        }
+
+        let mut config = self.config;
+        config.orientation = orientation;
+
+        // 重建只为换后端尺寸，控制器状态沿用原来的
+        let previous = core::mem::replace(self, St7735Builder::from(config).build()?);
+        self.power = previous.power;
+        self.scroll = previous.scroll;
+        self.sync = previous.sync;
+        self.apply_orientation()
+    }
+
+    /// 复位控制器：接了RST时拉低复位，否则发送SWRESET
+    pub fn reset<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), SpiError> {
+        self.port.reset(self.config.rst_pin, delay)
+    }
+
+    /// 当前电源状态
+    pub fn power_state(&self) -> PowerState {
+        self.power
+    }
+
+    /// 切换电源状态
+    ///
+    /// 离开睡眠先发SLPOUT并等待120ms；进入睡眠前先关闭显示输出，
+    /// SLPIN后再等待5ms。显存内容在各状态间保持不变。
+    pub fn set_power_state<D: DelayNs>(
+        &mut self,
+        state: PowerState,
+        delay: &mut D,
+    ) -> Result<(), SpiError> {
+        if state == self.power {
+            return Ok(());
+        }
+
+        if self.power == PowerState::Sleep {
+            self.port.write_command(Command::SlpOut, &[])?;
+            delay.delay_ms(120);
+        }
+
+        match state {
+            PowerState::On => {
+                self.port.write_command(Command::IdmOff, &[])?;
+                self.port.write_command(Command::DispOn, &[])?;
+            }
+            PowerState::Idle => {
+                self.port.write_command(Command::IdmOn, &[])?;
+                self.port.write_command(Command::DispOn, &[])?;
+            }
+            PowerState::Off => {
+                self.port.write_command(Command::DispOff, &[])?;
+            }
+            PowerState::Sleep => {
+                self.port.write_command(Command::DispOff, &[])?;
+                self.port.write_command(Command::SlpIn, &[])?;
+                delay.delay_ms(5);
+            }
+        }
+
+        self.power = state;
+        Ok(())
+    }
+
+    /// 进入（`true`）或退出（`false`）睡眠
+    pub fn sleep<D: DelayNs>(&mut self, asleep: bool, delay: &mut D) -> Result<(), SpiError> {
+        let state = if asleep {
+            PowerState::Sleep
+        } else {
+            PowerState::On
+        };
+        self.set_power_state(state, delay)
+    }
+
+    /// 定义硬件垂直滚动区域（VSCRDEF）
+    ///
+    /// 参数按0°方向的扫描行计，三者之和须等于面板高度；
+    /// 行列交换的方向（90°/270°）下扫描方向是水平的，不支持。
+    pub fn define_scroll_area(
+        &mut self,
+        top: u16,
+        height: u16,
+        bottom: u16,
+    ) -> Result<(), SpiError> {
+        let madctl = self.config.orientation.madctl();
+        if madctl & MADCTL_MV != 0 {
+            return Err(SpiError::Unsupported);
+        }
+        if height == 0 || top as u32 + height as u32 + bottom as u32 != self.config.height as u32 {
+            return Err(SpiError::InvalidParameter);
+        }
+
+        // 显存中的固定区要把可视区外的偏移行也算进去
+        let tfa = self.config.y_offset + top;
+        let bfa = self.config.gram_height.saturating_sub(tfa + height);
+        let [tfa_hi, tfa_lo] = tfa.to_be_bytes();
+        let [vsa_hi, vsa_lo] = height.to_be_bytes();
+        let [bfa_hi, bfa_lo] = bfa.to_be_bytes();
+        self.port.write_command(
+            Command::VscrDef,
+            &[tfa_hi, tfa_lo, vsa_hi, vsa_lo, bfa_hi, bfa_lo],
+        )?;
+
+        let area = ScrollArea {
+            top,
+            height,
+            bottom,
+        };
+        self.scroll = Some(VerticalScroller::new(area, madctl & MADCTL_MY != 0));
+        self.scroll_to(0)
+    }
+
+    /// 滚动到第`line`行（VSCSAD），即滚动区顶部显示原来的第`line`行
+    pub fn scroll_to(&mut self, line: u16) -> Result<(), SpiError> {
+        let scroller = self.scroll.as_mut().ok_or(SpiError::NotInitialized)?;
+        scroller.set_offset(line);
+
+        let ssa = self.config.y_offset + scroller.area().top + scroller.offset();
+        self.port.write_command(Command::VscSad, &ssa.to_be_bytes())
+    }
+
+    /// 相对当前位置滚动，正数向上卷动
+    pub fn scroll_by(&mut self, delta: i16) -> Result<(), SpiError> {
+        let scroller = self.scroll.ok_or(SpiError::NotInitialized)?;
+        let height = scroller.area().height as i32;
+        let line = (scroller.offset() as i32 + delta as i32).rem_euclid(height);
+        self.scroll_to(line as u16)
+    }
+
+    /// 当前滚动状态
+    pub fn scroller(&self) -> Option<VerticalScroller> {
+        self.scroll
+    }
+
+    /// 退出滚动模式（NORON），恢复正常显示
+    pub fn stop_scroll(&mut self) -> Result<(), SpiError> {
+        self.scroll = None;
+        self.port.write_command(Command::NorOn, &[])
+    }
+
+    /// 按滚动状态换算坐标的绘制视图，未定义滚动区时返回`None`
+    pub fn scroll_view(&mut self) -> Option<ScrollView<'_, Self>> {
+        let scroller = self.scroll?;
+        Some(ScrollView::new(self, scroller))
+    }
+
+    /// 写入MADCTL并同步偏移
+    fn apply_orientation(&mut self) -> Result<(), SpiError> {
+        self.port.send(Madctl::from_bits(self.config.madctl()))?;
+
+        let (dx, dy) = self.config.logical_offset();
+        self.set_offset(dx, dy);
+
+        Ok(())
+    }
+
+    /// 设置显存偏移
+    pub fn set_offset(&mut self, dx: u16, dy: u16) {
+        self.offset = (dx, dy);
+        with_backend!(&mut self.backend, display => display.set_offset(dx, dy))
+    }
+
+    /// 按指定颜色类型绘制的视图，仅直接模式可用
+    ///
+    /// 像素按配置的[`ColorMode`]打包后写入显存，例如在RGB666模式下
+    /// 用`color_view::<Rgb666>()`绘制渐变，不会先降到RGB565。
+    pub fn color_view<C>(&mut self) -> Result<ColorView<'_, C>, SpiError>
+    where
+        C: PixelColor + Into<Rgb888> + 'static,
+    {
+        if self.mode() != DisplayMode::Direct {
+            return Err(SpiError::Unsupported);
+        }
+        Ok(ColorView {
+            display: self,
+            _color: PhantomData,
+        })
+    }
+
+    /// 直接模式下绘制都走命令通道：按窗口成批写入，并跳过重复的CASET/RASET
+    ///
+    /// 缓冲模式绘制到后端的内存缓冲区，不经过总线。
+    fn draws_through_port(&self) -> bool {
+        self.mode() == DisplayMode::Direct
+    }
+
+    /// 逻辑坐标下的屏幕边界
+    fn logical_bounds(&self) -> Rectangle {
+        let (width, height) = self.config.logical_size();
+        Rectangle::new(Point::zero(), Size::new(width as u32, height as u32))
+    }
+
+    /// 按逻辑坐标设置窗口，`area`须已在屏幕范围内
+    fn set_area(&mut self, area: &Rectangle) -> Result<(), SpiError> {
+        let (dx, dy) = self.offset;
+        let x0 = area.top_left.x as u16 + dx;
+        let y0 = area.top_left.y as u16 + dy;
+        let x1 = x0 + area.size.width as u16 - 1;
+        let y1 = y0 + area.size.height as u16 - 1;
+
+        self.port.set_window(x0, y0, x1, y1)
+    }
+
+    /// 设置窗口并发送RAMWR，`area`须已在屏幕范围内
+    fn begin_write(&mut self, area: &Rectangle) -> Result<(), SpiError> {
+        self.set_area(area)?;
+        self.port.begin_stream(Command::RamWr)
+    }
+
+    /// 设置窗口并按颜色模式流式写入像素，`area`须已在屏幕范围内
+    fn write_native<C, I>(&mut self, area: &Rectangle, colors: I) -> Result<(), SpiError>
+    where
+        C: Into<Rgb888> + 'static,
+        I: IntoIterator<Item = C>,
+    {
+        self.begin_write(area)?;
+
+        let count = (area.size.width * area.size.height) as usize;
+        let mut packer = PixelPacker::new(self.config.color_mode);
+        let mut burst = [0u8; BURST_LEN];
+        let mut len = 0;
+        let mut out = [0u8; 3];
+
+        for color in colors.into_iter().take(count) {
+            let n = packer.push(color, &mut out);
+            burst[len..len + n].copy_from_slice(&out[..n]);
+            len += n;
+            if len + 3 > BURST_LEN {
+                self.port.write_data(&burst[..len])?;
+                len = 0;
+            }
+        }
+        let n = packer.finish(&mut out);
+        burst[len..len + n].copy_from_slice(&out[..n]);
+        len += n;
+
+        if len > 0 {
+            self.port.write_data(&burst[..len])?;
+        }
+        self.port.end_stream()
+    }
+
+    /// 单色填充：整批打包一次后重复发送，`area`须已在屏幕范围内
+    fn write_native_solid<C: Into<Rgb888> + Copy + 'static>(
+        &mut self,
+        area: &Rectangle,
+        color: C,
+    ) -> Result<(), SpiError> {
+        let mode = self.config.color_mode;
+        let count = (area.size.width * area.size.height) as usize;
+
+        // BURST_LEN对三种模式都能整除，一批内不会拆开像素
+        let per_burst = mode.pixels_in(BURST_LEN);
+        if count < per_burst {
+            return self.write_native(area, core::iter::repeat(color));
+        }
+
+        let mut burst = [0u8; BURST_LEN];
+        let mut packer = PixelPacker::new(mode);
+        let mut len = 0;
+        let mut out = [0u8; 3];
+        for _ in 0..per_burst {
+            let n = packer.push(color, &mut out);
+            burst[len..len + n].copy_from_slice(&out[..n]);
+            len += n;
+        }
+
+        self.begin_write(area)?;
+        for _ in 0..count / per_burst {
+            self.port.write_data(&burst)?;
+        }
+
+        let rest = count % per_burst;
+        if rest > 0 {
+            self.port.write_data(&burst[..mode.bytes_for(rest)])?;
+        }
+        self.port.end_stream()
+    }
+
+    /// 逐点绘制，同一行上连续的点合并为一个窗口
+    fn native_draw_iter<C, I>(&mut self, pixels: I) -> Result<(), SpiError>
+    where
+        C: PixelColor + Into<Rgb888> + 'static,
+        I: IntoIterator<Item = Pixel<C>>,
+    {
+        let bounds = self.logical_bounds();
+        let mut run: Vec<C, RUN_LEN> = Vec::new();
+        let mut start = Point::zero();
+
+        for Pixel(point, color) in pixels {
+            if !bounds.contains(point) {
+                continue;
+            }
+
+            let next = start + Point::new(run.len() as i32, 0);
+            if !run.is_empty() && (point != next || run.is_full()) {
+                let area = Rectangle::new(start, Size::new(run.len() as u32, 1));
+                self.write_native(&area, run.iter().copied())?;
+                run.clear();
+            }
+            if run.is_empty() {
+                start = point;
+            }
+            let _ = run.push(color);
+        }
+
+        if !run.is_empty() {
+            let area = Rectangle::new(start, Size::new(run.len() as u32, 1));
+            self.write_native(&area, run.iter().copied())?;
+        }
+        Ok(())
+    }
+
+    fn native_fill_contiguous<C, I>(&mut self, area: &Rectangle, colors: I) -> Result<(), SpiError>
+    where
+        C: PixelColor + Into<Rgb888> + 'static,
+        I: IntoIterator<Item = C>,
+    {
+        let clipped = area.intersection(&self.logical_bounds());
+        if clipped.size.width == 0 || clipped.size.height == 0 {
+            return Ok(());
+        }
+        if clipped == *area {
+            return self.write_native(area, colors);
+        }
+
+        // 部分越界时逐点裁剪，行内连续的点仍会合并
+        let pixels = area
+            .points()
+            .zip(colors)
+            .map(|(point, color)| Pixel(point, color));
+        self.native_draw_iter(pixels)
+    }
+
+    fn native_fill_solid<C>(&mut self, area: &Rectangle, color: C) -> Result<(), SpiError>
+    where
+        C: PixelColor + Into<Rgb888> + 'static,
+    {
+        let area = area.intersection(&self.logical_bounds());
+        if area.size.width == 0 || area.size.height == 0 {
+            return Ok(());
+        }
+        self.write_native_solid(&area, color)
+    }
+
+    /// 将绘制内容送到屏幕
+    ///
+    /// 先按[`FrameSync`]等待帧同步：缓冲模式下随后交换缓冲区，不会撕裂。
+    ///
+    /// 直接模式下绘制时已写入屏幕，这里的等待只起帧率节流作用，没有防撕裂效果。
+    /// 需要防撕裂时在写入前调用[`WindowWrite::wait_frame`]，
+    /// 或经[`crate::gfx::DoubleBuffered`]呈现（它在发送前等待）。
+    pub fn flush(&mut self) -> Result<(), SpiError> {
+        self.sync.wait()?;
+
+        match &mut self.backend {
+            #[cfg(feature = "st7735-lcd")]
+            Backend::Direct(_) => Ok(()),
+            #[cfg(feature = "st7735-lcd-doublebuffering")]
+            Backend::Buffered(display) => {
+                self.port.invalidate_window();
+                display.swap_buffers().map_err(|_| SpiError::TransferFailed)
+            }
+        }
+    }
+}
+
+impl DrawTarget for St7735Display {
+    type Color = Rgb565;
+    type Error = SpiError;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        if self.draws_through_port() {
+            return self.native_draw_iter(pixels);
+        }
+        with_backend!(&mut self.backend, display => display
+            .draw_iter(pixels)
+            .map_err(|_| SpiError::TransferFailed))
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        if self.draws_through_port() {
+            return self.native_fill_contiguous(area, colors);
+        }
+        with_backend!(&mut self.backend, display => display
+            .fill_contiguous(area, colors)
+            .map_err(|_| SpiError::TransferFailed))
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        if self.draws_through_port() {
+            return self.native_fill_solid(area, color);
+        }
+        with_backend!(&mut self.backend, display => display
+            .fill_solid(area, color)
+            .map_err(|_| SpiError::TransferFailed))
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        if self.draws_through_port() {
+            return self.native_fill_solid(&self.logical_bounds(), color);
+        }
+        with_backend!(&mut self.backend, display => display
+            .clear(color)
+            .map_err(|_| SpiError::TransferFailed))
+    }
+}
+
+/// 逻辑坐标，越界部分被裁掉；直接模式下按窗口成批发送
+impl WindowWrite for St7735Display {
+    type Error = SpiError;
+
+    fn write_window<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Rgb565>,
+    {
+        self.fill_contiguous(area, colors)
+    }
+
+    /// 直接模式、RGB565且窗口完全在屏内时整行转换、大块发送
+    fn write_rows<'a, R>(&mut self, area: &Rectangle, rows: R) -> Result<(), Self::Error>
+    where
+        R: IntoIterator<Item = &'a [Rgb565]>,
+    {
+        if area.size.width == 0 || area.size.height == 0 {
+            return Ok(());
+        }
+        let fast = self.draws_through_port()
+            && self.config.color_mode == ColorMode::Rgb565
+            && area.intersection(&self.logical_bounds()) == *area;
+        if !fast {
+            return self.fill_contiguous(area, rows.into_iter().flatten().copied());
+        }
+
+        self.begin_write(area)?;
+        stream_rows(area, rows, |bytes| self.port.write_data(bytes))?;
+        self.port.end_stream()
+    }
+
+    /// 按配置的[`FrameSync`]等待
+    fn wait_frame(&mut self) -> Result<(), Self::Error> {
+        self.sync.wait()
+    }
+}
+
+impl OriginDimensions for St7735Display {
+    fn size(&self) -> Size {
+        with_backend!(&self.backend, display => display.size())
+    }
+}
+
+/// 按颜色类型`C`绘制的视图，见[`St7735Display::color_view`]
+pub struct ColorView<'a, C> {
+    display: &'a mut St7735Display,
+    _color: PhantomData<C>,
+}
+
+impl<C> Dimensions for ColorView<'_, C> {
+    fn bounding_box(&self) -> Rectangle {
+        self.display.logical_bounds()
+    }
+}
+
+impl<C: PixelColor + Into<Rgb888> + 'static> DrawTarget for ColorView<'_, C> {
+    type Color = C;
+    type Error = SpiError;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.display.native_draw_iter(pixels)
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.display.native_fill_contiguous(area, colors)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.display.native_fill_solid(area, color)
+    }
+}
+
+/// 常见ST7735模组预设
+///
+/// 命名规则：`Tft<尺寸>_<宽>x<高>[变体]`，尺寸以英寸计（`144` = 1.44"）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Panel {
+    /// 1.44" 128x128，BGR，GM=011（132x132显存）
+    Tft144_128x128,
+    /// 1.8" 128x160 黑标，RGB，无偏移
+    Tft18_128x160,
+    /// 1.8" 128x160 绿标，BGR，132x162显存
+    Tft18_128x160Green,
+    /// 0.96" 80x160 IPS，BGR，颜色反转
+    Tft096_80x160,
+}
+
+impl Panel {
+    /// 按回读的控制器标识（ID1 ID2 ID3）与0°方向尺寸选择预设
+    ///
+    /// 标识不含模组尺寸，尺寸由调用方给出。可识别的组合：
+    ///
+    /// - `7C xx xx`（Sitronix ST7735系列）、128x128：[`Panel::Tft144_128x128`]
+    /// - `7C xx xx`、80x160：[`Panel::Tft096_80x160`]
+    /// - `7C 89 F0`（ST7735R/S出厂标识）、128x160：[`Panel::Tft18_128x160Green`]
+    /// - 其余`7C xx xx`、128x160：[`Panel::Tft18_128x160`]
+    ///
+    /// 其他组合返回`None`。
+    pub fn from_id(id: [u8; 3], width: u16, height: u16) -> Option<Panel> {
+        match (id, width, height) {
+            ([0x7C, _, _], 128, 128) => Some(Panel::Tft144_128x128),
+            ([0x7C, _, _], 80, 160) => Some(Panel::Tft096_80x160),
+            ([0x7C, 0x89, 0xF0], 128, 160) => Some(Panel::Tft18_128x160Green),
+            ([0x7C, _, _], 128, 160) => Some(Panel::Tft18_128x160),
+            _ => None,
+        }
+    }
+
+    /// 预设对应的硬件参数（引脚使用默认值）
+    pub fn config(self) -> St7735Config {
+        let base = St7735Config::default();
+        match self {
+            Panel::Tft144_128x128 => St7735Config {
+                width: 128,
+                height: 128,
+                x_offset: 2,
+                y_offset: 1,
+                gram_width: 132,
+                gram_height: 132,
+                rgb: false,
+                inverted: false,
+                orientation: Orientation::new(Rotation::Deg0),
+                gamma: Some(Gamma::ST7735R),
+                ..base
+            },
+            Panel::Tft18_128x160 => St7735Config {
+                width: 128,
+                height: 160,
+                x_offset: 0,
+                y_offset: 0,
+                gram_width: 128,
+                gram_height: 160,
+                rgb: true,
+                inverted: false,
+                orientation: Orientation::new(Rotation::Deg0),
+                gamma: Some(Gamma::ST7735R),
+                ..base
+            },
+            Panel::Tft18_128x160Green => St7735Config {
+                width: 128,
+                height: 160,
+                x_offset: 2,
+                y_offset: 1,
+                gram_width: 132,
+                gram_height: 162,
+                rgb: false,
+                inverted: false,
+                orientation: Orientation::new(Rotation::Deg0),
+                gamma: Some(Gamma::ST7735R),
+                ..base
+            },
+            Panel::Tft096_80x160 => St7735Config {
+                width: 80,
+                height: 160,
+                x_offset: 26,
+                y_offset: 1,
+                gram_width: 132,
+                gram_height: 162,
+                rgb: false,
+                inverted: true,
+                orientation: Orientation::new(Rotation::Deg0),
+                gamma: Some(Gamma::ST7735R),
+                ..base
+            },
+        }
+    }
+}
+
+/// ST7735硬件配置
+#[derive(Debug, Clone, Copy)]
+pub struct St7735Config {
+    /// DC引脚（数据/命令选择），3线模式下不使用
+    pub dc_pin: u32,
+    /// RST引脚（复位，可选）
+    pub rst_pin: Option<u32>,
+    /// CS引脚（片选，可选），同一总线上接多块屏时必须设置
+    pub cs_pin: Option<u32>,
+    /// 屏幕宽度（0°方向）
+    pub width: u16,
+    /// 屏幕高度（0°方向）
+    pub height: u16,
+    /// 0°方向下可视区在显存中的列偏移
+    pub x_offset: u16,
+    /// 0°方向下可视区在显存中的行偏移
+    pub y_offset: u16,
+    /// 控制器显存宽度
+    pub gram_width: u16,
+    /// 控制器显存高度
+    pub gram_height: u16,
+    /// RGB模式（true = RGB，false = BGR）
+    pub rgb: bool,
+    /// 颜色反转
+    pub inverted: bool,
+    /// 显示方向
+    pub orientation: Orientation,
+    /// 刷新模式
+    pub mode: DisplayMode,
+    /// SPI接线方式
+    pub interface: SpiInterface,
+    /// 接口像素格式，非RGB565时仅直接模式可用
+    pub color_mode: ColorMode,
+    /// 防撕裂同步方式
+    pub frame_sync: FrameSync,
+    /// 伽马校正，`None`时保持控制器默认曲线
+    pub gamma: Option<Gamma>,
+    /// 帧率，`None`时沿用初始化序列的值
+    pub frame_rates: Option<FrameRates>,
+    /// 电源控制，`None`时沿用初始化序列的值
+    pub power_control: Option<PowerControl>,
+    /// 替换内置初始化序列的脚本，应包含SLPOUT与DISPON；
+    /// 脚本之后仍会写入方向以及上面为`Some`的调校参数
+    pub init_script: Option<InitScript<'static>>,
+}
+
+impl St7735Config {
+    /// 使用模组预设创建配置
+    pub fn preset(panel: Panel) -> Self {
+        panel.config()
+    }
+
+    /// 换用预设的模组参数：显存几何、偏移、颜色顺序、反色与伽马
+    ///
+    /// 引脚、方向、刷新模式与调校参数保持不变；未指定初始化脚本时沿用预设的（内置序列）。
+    pub fn with_panel(self, panel: Panel) -> Self {
+        let preset = panel.config();
+        St7735Config {
+            width: preset.width,
+            height: preset.height,
+            x_offset: preset.x_offset,
+            y_offset: preset.y_offset,
+            gram_width: preset.gram_width,
+            gram_height: preset.gram_height,
+            rgb: preset.rgb,
+            inverted: preset.inverted,
+            gamma: preset.gamma,
+            init_script: self.init_script.or(preset.init_script),
+            ..self
+        }
+    }
+
+    /// 旋转后的逻辑尺寸 (宽, 高)
+    pub fn logical_size(&self) -> (u16, u16) {
+        if self.orientation.rotation.is_swapped() {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        }
+    }
+
+    /// 写入控制器的MADCTL值（方向 + 颜色顺序）
+    pub fn madctl(&self) -> u8 {
+        let order = if self.rgb { 0 } else { MADCTL_BGR };
+        self.orientation.madctl() | order
+    }
+
+    /// 旋转后写入CASET/RASET的偏移 (x, y)
+    ///
+    /// 镜像方向上的偏移取显存另一侧的余量，交换行列时两者互换。
+    pub fn logical_offset(&self) -> (u16, u16) {
+        let left = self.x_offset;
+        let right = self.gram_width.saturating_sub(self.width + self.x_offset);
+        let top = self.y_offset;
+        let bottom = self.gram_height.saturating_sub(self.height + self.y_offset);
+
+        let madctl = self.orientation.madctl();
+        let col = if madctl & MADCTL_MX != 0 { right } else { left };
+        let row = if madctl & MADCTL_MY != 0 { bottom } else { top };
+
+        if madctl & MADCTL_MV != 0 {
+            (row, col)
+        } else {
+            (col, row)
+        }
+    }
+
+    /// 实际使用的DC引脚，3线模式下为`None`
+    pub fn dc(&self) -> Option<u32> {
+        match self.interface {
+            SpiInterface::FourWire => Some(self.dc_pin),
+            SpiInterface::ThreeWire => None,
+        }
+    }
+
+    /// 检查配置，不访问硬件
+    ///
+    /// 覆盖排针号范围、引脚重复、控制器显存上限以及偏移加尺寸越界。
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let te_pin = match self.frame_sync {
+            FrameSync::Te(pin) => Some(pin),
+            _ => None,
+        };
+        let pins = [self.dc(), self.rst_pin, self.cs_pin, te_pin];
+
+        for (index, pin) in pins.iter().enumerate() {
+            let Some(pin) = *pin else { continue };
+            if !(1..=16).contains(&pin) {
+                return Err(ConfigError::PinOutOfRange { pin });
+            }
+            if pins[index + 1..].contains(&Some(pin)) {
+                return Err(ConfigError::DuplicatePin { pin });
+            }
+        }
+
+        if self.width == 0 || self.height == 0 {
+            return Err(ConfigError::EmptySize);
+        }
+        if self.gram_width > MAX_GRAM_WIDTH || self.gram_height > MAX_GRAM_HEIGHT {
+            return Err(ConfigError::GramTooLarge {
+                width: self.gram_width,
+                height: self.gram_height,
+                max_width: MAX_GRAM_WIDTH,
+                max_height: MAX_GRAM_HEIGHT,
+            });
+        }
+        if self.x_offset as u32 + self.width as u32 > self.gram_width as u32 {
+            return Err(ConfigError::ColumnOverflow {
+                offset: self.x_offset,
+                width: self.width,
+                gram_width: self.gram_width,
+            });
+        }
+        if self.y_offset as u32 + self.height as u32 > self.gram_height as u32 {
+            return Err(ConfigError::RowOverflow {
+                offset: self.y_offset,
+                height: self.height,
+                gram_height: self.gram_height,
+            });
+        }
+        if self.mode == DisplayMode::Buffered
+            && (self.interface == SpiInterface::ThreeWire || self.color_mode != ColorMode::Rgb565)
+        {
+            return Err(ConfigError::BufferedModeUnsupported);
+        }
+        if let Some(script) = self.init_script {
+            script.validate()?;
+        }
+
+        Ok(())
+    }
+
+    /// 一帧的扫描时间（微秒），按正常模式的FRMCTR1计算
+    pub fn frame_period_us(&self) -> u32 {
+        let normal = self.frame_rates.unwrap_or_default().normal;
+        // 扫描行数由GM决定，最多160行
+        normal.frame_period_us(self.gram_height.min(160))
+    }
+}
+
+impl Default for St7735Config {
+    fn default() -> Self {
+        Self {
+            dc_pin: 14, // 对应实际引脚`2`
+            rst_pin: None,
+            cs_pin: None,
+            width: 128,
+            height: 128,
+            x_offset: 2,
+            y_offset: 1,
+            gram_width: 132,
+            gram_height: 132,
+            rgb: false,
+            inverted: false,
+            orientation: Orientation::default(),
+            mode: DisplayMode::default(),
+            interface: SpiInterface::FourWire,
+            color_mode: ColorMode::Rgb565,
+            frame_sync: FrameSync::Off,
+            gamma: None,
+            frame_rates: None,
+            power_control: None,
+            init_script: None,
+        }
+    }
+}
+
+/// ST7735显示构建器
+pub struct St7735Builder {
+    config: St7735Config,
+}
+
+impl St7735Builder {
+    /// 创建新的构建器
+    pub fn new() -> Self {
+        Self {
+            config: St7735Config::default(),
+        }
+    }
+
+    /// 设置DC引脚
+    pub fn dc_pin(mut self, pin: u32) -> Self {
+        self.config.dc_pin = pin;
+        self
+    }
+
+    /// 使用3线（9位）SPI，不需要DC引脚
+    pub fn three_wire(mut self) -> Self {
+        self.config.interface = SpiInterface::ThreeWire;
+        self
+    }
+
+    /// 设置RST引脚
+    pub fn rst_pin(mut self, pin: u32) -> Self {
+        self.config.rst_pin = Some(pin);
+        self
+    }
+
+    /// 设置CS引脚
+    pub fn cs_pin(mut self, pin: u32) -> Self {
+        self.config.cs_pin = Some(pin);
+        self
+    }
+
+    /// 设置屏幕尺寸
+    pub fn size(mut self, width: u16, height: u16) -> Self {
+        self.config.width = width;
+        self.config.height = height;
+        self
+    }
+
+    /// 应用模组预设（保留已设置的引脚）
+    pub fn preset(mut self, panel: Panel) -> Self {
+        self.config = St7735Config {
+            dc_pin: self.config.dc_pin,
+            rst_pin: self.config.rst_pin,
+            cs_pin: self.config.cs_pin,
+            interface: self.config.interface,
+            frame_sync: self.config.frame_sync,
+            init_script: self.config.init_script,
+            ..panel.config()
+        };
+        self
+    }
+
+    /// 设置0°方向下的显存偏移
+    pub fn offset(mut self, x: u16, y: u16) -> Self {
+        self.config.x_offset = x;
+        self.config.y_offset = y;
+        self
+    }
+
+    /// 设置控制器显存尺寸
+    pub fn gram_size(mut self, width: u16, height: u16) -> Self {
+        self.config.gram_width = width;
+        self.config.gram_height = height;
+        self
+    }
+
+    /// 设置显示方向
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.config.orientation = orientation;
+        self
+    }
+
+    /// 设置显示旋转（保留镜像设置）
+    pub fn rotation(mut self, rotation: Rotation) -> Self {
+        self.config.orientation.rotation = rotation;
+        self
+    }
+
+    /// 设置镜像
+    pub fn mirror(mut self, mirror_x: bool, mirror_y: bool) -> Self {
+        self.config.orientation = self.config.orientation.mirrored(mirror_x, mirror_y);
+        self
+    }
+
+    /// 设置刷新模式
+    pub fn mode(mut self, mode: DisplayMode) -> Self {
+        self.config.mode = mode;
+        self
+    }
+
+    /// 设置接口像素格式
+    pub fn color_mode(mut self, mode: ColorMode) -> Self {
+        self.config.color_mode = mode;
+        self
+    }
+
+    /// 设置TE引脚，刷新时等待垂直消隐
+    pub fn te_pin(mut self, pin: u32) -> Self {
+        self.config.frame_sync = FrameSync::Te(pin);
+        self
+    }
+
+    /// 设置防撕裂同步方式
+    pub fn frame_sync(mut self, sync: FrameSync) -> Self {
+        self.config.frame_sync = sync;
+        self
+    }
+
+    /// 设置伽马表
+    pub fn gamma(mut self, gamma: Gamma) -> Self {
+        self.config.gamma = Some(gamma);
+        self
+    }
+
+    /// 设置帧率
+    pub fn frame_rates(mut self, rates: FrameRates) -> Self {
+        self.config.frame_rates = Some(rates);
+        self
+    }
+
+    /// 设置电源控制寄存器
+    pub fn power_control(mut self, power: PowerControl) -> Self {
+        self.config.power_control = Some(power);
+        self
+    }
+
+    /// 用自定义脚本代替内置初始化序列
+    pub fn init_script(mut self, script: InitScript<'static>) -> Self {
+        self.config.init_script = Some(script);
+        self
+    }
+
+    /// 设置RGB模式
+    pub fn rgb(mut self, rgb: bool) -> Self {
+        self.config.rgb = rgb;
+        self
+    }
+
+    /// 设置颜色反转
+    pub fn inverted(mut self, inverted: bool) -> Self {
+        self.config.inverted = inverted;
+        self
+    }
+
+    /// 检查当前配置
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.config.validate()
+    }
+
+    /// 构建ST7735显示驱动，配置有误时在访问硬件前返回
+    pub fn build(self) -> Result<St7735Display, SpiError> {
+        self.config.validate()?;
+
+        // 创建SPI设备，各屏通过片选共享总线
+        let spi_device = EbdHalSpiDevice::with_optional_cs(self.config.cs_pin)
+            .ok_or(SpiError::NotInitialized)?;
+
+        let (width, height) = self.config.logical_size();
+
+        // 创建DC引脚，3线模式下为空操作
+        let mut dc_pin = OptionalPin::new(self.config.dc());
+        dc_pin.set_high().map_err(|_| SpiError::GpioError)?;
+
+        // 根据刷新模式创建对应的后端
+        let backend = match (self.config.mode, dc_pin) {
+            #[cfg(feature = "st7735-lcd")]
+            (DisplayMode::Direct, dc_pin) => {
+                // 创建RST引脚，未接线时为空操作
+                let mut rst_pin = OptionalPin::new(self.config.rst_pin);
+                rst_pin.set_high().map_err(|_| SpiError::GpioError)?;
+
+                Backend::Direct(ST7735::new(
+                    spi_device,
+                    dc_pin,
+                    rst_pin,
+                    self.config.rgb,
+                    self.config.inverted,
+                    width as u32,
+                    height as u32,
+                ))
+            }
+
+            // 双缓冲版本不需要RST引脚，缓冲区固定为RGB565，交换时需要DC引脚
+            #[cfg(feature = "st7735-lcd-doublebuffering")]
+            (DisplayMode::Buffered, OptionalPin::Gpio(dc_pin))
+                if self.config.color_mode == ColorMode::Rgb565 =>
+            {
+                Backend::Buffered(ST7735Buffered::new(
+                    spi_device,
+                    dc_pin,
+                    self.config.rgb,
+                    width as u32,
+                    height as u32,
+                ))
+            }
+
+            // 对应特性未启用
+            #[allow(unreachable_patterns)]
+            _ => return Err(SpiError::Unsupported),
+        };
+
+        let port = CommandPort::new(self.config.dc(), self.config.cs_pin)?;
+        let sync = FrameSyncer::new(self.config.frame_sync, self.config.frame_period_us());
+
+        Ok(St7735Display {
+            backend,
+            port,
+            config: self.config,
+            power: PowerState::On,
+            scroll: None,
+            sync,
+            offset: self.config.logical_offset(),
+        })
+    }
+}
+
+impl From<St7735Config> for St7735Builder {
+    fn from(config: St7735Config) -> Self {
+        Self { config }
+    }
+}
+
+impl Default for St7735Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 便捷函数：初始化QSPI并创建显示驱动
+pub fn init_display(config: St7735Config) -> Result<St7735Display, SpiError> {
+    init_panel(config)
+}
+
+/// 便捷函数：使用默认配置初始化显示
+pub fn init_default_display() -> Result<St7735Display, SpiError> {
+    init_default_panel()
+}
+
+/// 命令通道：绕过显示后端直接向控制器写命令
+///
+/// QSPI与GPIO都是全局硬件，这里只是另一组句柄，
+/// 用于后端未提供的命令（如带镜像位的MADCTL）。
+pub(crate) struct CommandPort {
+    transport: Transport,
+    /// 上次写入的列范围（CASET）
+    columns: Option<(u16, u16)>,
+    /// 上次写入的行范围（RASET）
+    rows: Option<(u16, u16)>,
+}
+
+enum Transport {
+    /// 4线：独立的DC引脚
+    FourWire(RawPanel),
+    /// 3线：D/C作为第9位随数据发送
+    ThreeWire(ThreeWireSpi),
+}
+
+impl CommandPort {
+    /// `dc_pin`为`None`时使用3线（9位）传输
+    pub(crate) fn new(dc_pin: Option<u32>, cs_pin: Option<u32>) -> Result<Self, SpiError> {
+        let spi = EbdHalSpiDevice::with_optional_cs(cs_pin).ok_or(SpiError::NotInitialized)?;
+        let transport = match dc_pin {
+            Some(pin) => Transport::FourWire(RawPanel::new(spi, EbdHalGpio::new(pin))),
+            None => Transport::ThreeWire(ThreeWireSpi::new(spi)),
+        };
+        Ok(Self {
+            transport,
+            columns: None,
+            rows: None,
+        })
+    }
+
+    /// 设置写入窗口（显存坐标，含端点），与上次相同的CASET/RASET不再发送
+    pub(crate) fn set_window(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+    ) -> Result<(), SpiError> {
+        if self.columns != Some((x0, x1)) {
+            self.send(Caset::new(x0, x1))?;
+            self.columns = Some((x0, x1));
+        }
+        if self.rows != Some((y0, y1)) {
+            self.send(Raset::new(y0, y1))?;
+            self.rows = Some((y0, y1));
+        }
+        Ok(())
+    }
+
+    /// 窗口可能被其它途径改写时清空缓存
+    pub(crate) fn invalidate_window(&mut self) {
+        self.columns = None;
+        self.rows = None;
+    }
+
+    /// 复位控制器
+    ///
+    /// 时序取自数据手册：RESX低电平至少10us，释放后等待120ms；
+    /// SWRESET后同样需要120ms才能发送SLPOUT。
+    pub(crate) fn reset<D: DelayNs>(
+        &mut self,
+        rst_pin: Option<u32>,
+        delay: &mut D,
+    ) -> Result<(), SpiError> {
+        let mut rst = OptionalPin::new(rst_pin);
+
+        if rst.is_connected() {
+            rst.set_high().map_err(|_| SpiError::GpioError)?;
+            delay.delay_ms(1);
+            rst.set_low().map_err(|_| SpiError::GpioError)?;
+            delay.delay_us(20);
+            rst.set_high().map_err(|_| SpiError::GpioError)?;
+        } else {
+            self.write_command(Command::SwReset, &[])?;
+        }
+        delay.delay_ms(120);
+        self.invalidate_window();
+
+        Ok(())
+    }
+
+    /// 开始一段数据流（如RAMWR），随后用[`CommandPort::write_data`]分批发送
+    pub(crate) fn begin_stream(&mut self, command: impl Into<u8>) -> Result<(), SpiError> {
+        match &mut self.transport {
+            Transport::FourWire(panel) => panel.begin(command),
+            // 3线下不能补NOP，否则数据到达前RAMWR就结束了
+            Transport::ThreeWire(bus) => bus.write_command(command.into()),
+        }
+    }
+
+    /// 发送数据
+    pub(crate) fn write_data(&mut self, data: &[u8]) -> Result<(), SpiError> {
+        match &mut self.transport {
+            Transport::FourWire(panel) => panel.write_data(data),
+            Transport::ThreeWire(bus) => bus.write_data(data),
+        }
+    }
+
+    /// 结束数据流，发出3线传输中暂存的部分
+    pub(crate) fn end_stream(&mut self) -> Result<(), SpiError> {
+        match &mut self.transport {
+            Transport::FourWire(_) => Ok(()),
+            Transport::ThreeWire(bus) => bus.flush(),
+        }
+    }
+
+    /// 发送读命令并读回`buffer.len()`字节
+    ///
+    /// 片选拉高会中止读操作，命令与读回须在同一次事务内完成；
+    /// 3线模式下SDA是双向的，不支持回读。
+    pub(crate) fn read_command(
+        &mut self,
+        command: impl Into<u8>,
+        buffer: &mut [u8],
+    ) -> Result<(), SpiError> {
+        match &mut self.transport {
+            Transport::FourWire(panel) => panel.read(command, buffer),
+            Transport::ThreeWire(_) => Err(SpiError::Unsupported),
+        }
+    }
+
+    /// 发送命令及其参数
+    pub(crate) fn write_command(
+        &mut self,
+        command: impl Into<u8>,
+        params: &[u8],
+    ) -> Result<(), SpiError> {
+        match &mut self.transport {
+            Transport::FourWire(panel) => panel.command(command, params),
+            Transport::ThreeWire(bus) => {
+                bus.write_command(command.into())?;
+                bus.write_data(params)?;
+                bus.flush()
+            }
+        }
+    }
+
+    /// 发送带类型参数的命令
+    pub(crate) fn send<P: CommandParams>(&mut self, params: P) -> Result<(), SpiError> {
+        self.write_command(P::COMMAND, params.params().as_ref())
+    }
+}
+
+/// ST7735系列控制器显存上限（GM=000）
+const MAX_GRAM_WIDTH: u16 = 132;
+const MAX_GRAM_HEIGHT: u16 = 162;
+
+/// 逐点绘制时一次合并的最多像素数
+const RUN_LEN: usize = 32;
+
+impl PanelDriver for St7735Display {
+    type Config = St7735Config;
+
+    fn create(config: St7735Config) -> Result<Self, SpiError> {
+        St7735Builder::from(config).build()
+    }
+
+    fn chip_select(config: &St7735Config) -> Option<u32> {
+        config.cs_pin
+    }
+
+    fn init<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), SpiError> {
+        St7735Display::init(self, delay)
+    }
+
+    fn orientation(&self) -> Orientation {
+        self.config.orientation
+    }
+
+    fn set_orientation(&mut self, orientation: Orientation) -> Result<(), SpiError> {
+        St7735Display::set_orientation(self, orientation)
+    }
+
+    fn power_state(&self) -> PowerState {
+        self.power
+    }
+
+    fn set_power_state<D: DelayNs>(
+        &mut self,
+        state: PowerState,
+        delay: &mut D,
+    ) -> Result<(), SpiError> {
+        St7735Display::set_power_state(self, state, delay)
+    }
+
+    fn flush(&mut self) -> Result<(), SpiError> {
+        St7735Display::flush(self)
+    }
+
+    fn logical_size(&self) -> (u16, u16) {
+        self.config.logical_size()
+    }
+}
+
+/// ST7735显示管理器
+pub type St7735Manager = DisplayManager<St7735Display>;
+
+impl DisplayManager<St7735Display> {
+    /// 使用模组预设创建显示管理器
+    pub fn with_preset(panel: Panel) -> Result<Self, SpiError> {
+        Self::new(St7735Config::preset(panel))
+    }
+
+    /// 回读控制器标识后按对应预设创建显示管理器
+    ///
+    /// 标识经[`Panel::from_id`]与`fallback`的尺寸一起映射到预设（其中列出了可识别的标识），
+    /// 偏移、颜色顺序、反色与伽马取自预设，其余沿用`fallback`，见[`St7735Config::with_panel`]。
+    /// 无法回读、标识未知或尺寸没有对应预设时直接按`fallback`创建；
+    /// 识别为本驱动不支持的控制器（ST7789、ILI9341）时返回[`SpiError::Unsupported`]。
+    pub fn auto(fallback: St7735Config) -> Result<Self, SpiError> {
+        fallback.validate()?;
+        ecos_ssc1::qspi::init_qspi(0);
+
+        // 3线模式无法回读
+        if fallback.interface == SpiInterface::ThreeWire {
+            return Self::new(fallback);
+        }
+
+        let detected = probe(
+            fallback.dc_pin,
+            fallback.cs_pin,
+            fallback.rst_pin,
+            &mut EbdHalDelay,
+        )?;
+        let Some(id) = detected else {
+            return Self::new(fallback);
+        };
+        match id.controller {
+            None => Self::new(fallback),
+            Some(Controller::St7735) => {
+                let config = match Panel::from_id(id.id, fallback.width, fallback.height) {
+                    Some(panel) => fallback.with_panel(panel),
+                    None => fallback,
+                };
+                Self::new(config)
+            }
+            Some(Controller::St7789 | Controller::Ili9341) => Err(SpiError::Unsupported),
+        }
+    }
+
+    /// 当前硬件配置
+    pub fn config(&self) -> &St7735Config {
+        self.display.config()
+    }
+
+    /// 运行时替换伽马表
+    pub fn set_gamma(&mut self, gamma: Gamma) -> Result<(), SpiError> {
+        self.display.set_gamma(gamma)
+    }
+
+    /// 读回显存中的区域，见[`St7735Display::read_region`]
+    pub fn read_region(
+        &mut self,
+        area: &Rectangle,
+        pixels: &mut [Rgb565],
+    ) -> Result<usize, SpiError> {
+        self.display.read_region(area, pixels)
+    }
+
+    /// 把整屏内容以文本形式输出，见[`St7735Display::screenshot_to`]
+    pub fn screenshot_to<W: core::fmt::Write>(&mut self, writer: &mut W) -> Result<(), SpiError> {
+        self.display.screenshot_to(writer)
+    }
+
+    /// 定义硬件垂直滚动区域
+    pub fn define_scroll_area(
+        &mut self,
+        top: u16,
+        height: u16,
+        bottom: u16,
+    ) -> Result<(), SpiError> {
+        self.display.define_scroll_area(top, height, bottom)
+    }
+
+    /// 滚动到第`line`行
+    pub fn scroll_to(&mut self, line: u16) -> Result<(), SpiError> {
+        self.display.scroll_to(line)
+    }
+
+    /// 相对当前位置滚动
+    pub fn scroll_by(&mut self, delta: i16) -> Result<(), SpiError> {
+        self.display.scroll_by(delta)
+    }
+
+    /// 退出滚动模式
+    pub fn stop_scroll(&mut self) -> Result<(), SpiError> {
+        self.display.stop_scroll()
+    }
+
+    /// 按滚动状态换算坐标的绘制视图
+    pub fn scroll_view(&mut self) -> Option<ScrollView<'_, St7735Display>> {
+        self.display.scroll_view()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PANELS: [Panel; 4] = [
+        Panel::Tft144_128x128,
+        Panel::Tft18_128x160,
+        Panel::Tft18_128x160Green,
+        Panel::Tft096_80x160,
+    ];
+
+    #[test]
+    fn presets_are_valid() {
+        assert_eq!(St7735Config::default().validate(), Ok(()));
+        for panel in PANELS {
+            let config = St7735Config::preset(panel);
+            assert_eq!(config.validate(), Ok(()), "{panel:?}");
+
+            // 各方向、3线与缓冲模式下同样有效
+            for rotation in [Rotation::Deg90, Rotation::Deg180, Rotation::Deg270] {
+                let config = St7735Config {
+                    orientation: Orientation::new(rotation),
+                    ..config
+                };
+                assert_eq!(config.validate(), Ok(()), "{panel:?} {rotation:?}");
+            }
+            let three_wire = St7735Config {
+                interface: SpiInterface::ThreeWire,
+                ..config
+            };
+            assert_eq!(three_wire.validate(), Ok(()), "{panel:?}");
+            let buffered = St7735Config {
+                mode: DisplayMode::Buffered,
+                ..config
+            };
+            assert_eq!(buffered.validate(), Ok(()), "{panel:?}");
+        }
+    }
+
+    #[test]
+    fn rejects_pins_out_of_range() {
+        for pin in [0, 17] {
+            let config = St7735Config {
+                rst_pin: Some(pin),
+                ..St7735Config::default()
+            };
+            assert_eq!(config.validate(), Err(ConfigError::PinOutOfRange { pin }));
+        }
+        let config = St7735Config {
+            frame_sync: FrameSync::Te(20),
+            ..St7735Config::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::PinOutOfRange { pin: 20 })
+        );
+    }
+
+    #[test]
+    fn rejects_duplicate_pins() {
+        let config = St7735Config {
+            cs_pin: Some(14),
+            ..St7735Config::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::DuplicatePin { pin: 14 })
+        );
+
+        let config = St7735Config {
+            rst_pin: Some(5),
+            frame_sync: FrameSync::Te(5),
+            ..St7735Config::default()
+        };
+        assert_eq!(config.validate(), Err(ConfigError::DuplicatePin { pin: 5 }));
+
+        // 3线模式不占用DC引脚
+        let config = St7735Config {
+            interface: SpiInterface::ThreeWire,
+            ..config
+        };
+        assert_eq!(config.validate(), Err(ConfigError::DuplicatePin { pin: 5 }));
+        let config = St7735Config {
+            interface: SpiInterface::ThreeWire,
+            cs_pin: Some(14),
+            ..St7735Config::default()
+        };
+        assert_eq!(config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn rejects_empty_size() {
+        for (width, height) in [(0, 128), (128, 0)] {
+            let config = St7735Config {
+                width,
+                height,
+                ..St7735Config::default()
+            };
+            assert_eq!(config.validate(), Err(ConfigError::EmptySize));
+        }
+    }
+
+    #[test]
+    fn rejects_gram_beyond_controller() {
+        let config = St7735Config {
+            gram_width: 133,
+            ..St7735Config::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::GramTooLarge {
+                width: 133,
+                height: 132,
+                max_width: MAX_GRAM_WIDTH,
+                max_height: MAX_GRAM_HEIGHT,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_visible_area_beyond_gram() {
+        let config = St7735Config {
+            x_offset: 5,
+            ..St7735Config::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::ColumnOverflow {
+                offset: 5,
+                width: 128,
+                gram_width: 132,
+            })
+        );
+
+        let config = St7735Config {
+            height: 132,
+            ..St7735Config::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::RowOverflow {
+                offset: 1,
+                height: 132,
+                gram_height: 132,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_buffered_mode() {
+        let buffered = St7735Config {
+            mode: DisplayMode::Buffered,
+            ..St7735Config::default()
+        };
+        let config = St7735Config {
+            interface: SpiInterface::ThreeWire,
+            ..buffered
+        };
+        assert_eq!(config.validate(), Err(ConfigError::BufferedModeUnsupported));
+        let config = St7735Config {
+            color_mode: ColorMode::Rgb666,
+            ..buffered
+        };
+        assert_eq!(config.validate(), Err(ConfigError::BufferedModeUnsupported));
+
+        // 直接模式下两者都可用
+        let config = St7735Config {
+            interface: SpiInterface::ThreeWire,
+            color_mode: ColorMode::Rgb444,
+            ..St7735Config::default()
+        };
+        assert_eq!(config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn rejects_malformed_init_script() {
+        // MADCTL声明2个参数，只给了1个
+        let config = St7735Config {
+            init_script: Some(InitScript::Encoded(&[0x36, 0x02, 0xC8])),
+            ..St7735Config::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::MalformedInitScript { offset: 0 })
+        );
+
+        let config = St7735Config {
+            init_script: Some(InitScript::ST7735R),
+            ..St7735Config::default()
+        };
+        assert_eq!(config.validate(), Ok(()));
+    }
+}
@@ -0,0 +1,103 @@
+use core::fmt::Write;
+
+use embedded_graphics_core::{
+    geometry::{Point, Size},
+    pixelcolor::{IntoStorage, Rgb565},
+    primitives::Rectangle,
+};
+
+use super::cmd::Command;
+use super::{MAX_GRAM_HEIGHT, St7735Display};
+use crate::adapter::spi::SpiError;
+
+/// 一行最多的像素数：旋转90°时逻辑宽度为显存高度
+const MAX_ROW: usize = MAX_GRAM_HEIGHT as usize;
+/// RAMRD每行读回的最大字节数：1字节哑数据 + 每像素3字节
+const ROW_BYTES: usize = 1 + MAX_ROW * 3;
+
+impl St7735Display {
+    /// 读回显存中`area`（逻辑坐标）的像素，返回写入`pixels`的个数
+    ///
+    /// 需要接SDO/MISO且为4线接口。`area`先按屏幕裁剪，
+    /// `pixels`不足以容纳裁剪后的区域时返回`InvalidParameter`。
+    /// 串行接口下RAMRD固定按RGB666返回，这里转换为RGB565。
+    pub fn read_region(
+        &mut self,
+        area: &Rectangle,
+        pixels: &mut [Rgb565],
+    ) -> Result<usize, SpiError> {
+        let area = area.intersection(&self.logical_bounds());
+        let count = (area.size.width * area.size.height) as usize;
+        if pixels.len() < count {
+            return Err(SpiError::InvalidParameter);
+        }
+
+        let width = area.size.width as usize;
+        let mut chunks = pixels[..count].chunks_exact_mut(width.max(1));
+        self.read_rows(&area, |row| {
+            if let Some(chunk) = chunks.next() {
+                chunk.copy_from_slice(row);
+            }
+            Ok(())
+        })?;
+
+        Ok(count)
+    }
+
+    /// 把整屏内容以文本形式输出，例如传入`ecos_ssc1::uart::UartWriter`经串口发出
+    ///
+    /// 格式：首行`SCREENSHOT <宽> <高> RGB565`，随后每行像素一行，
+    /// 每像素4位十六进制（大端），最后一行`END`。
+    pub fn screenshot_to<W: Write>(&mut self, writer: &mut W) -> Result<(), SpiError> {
+        let bounds = self.logical_bounds();
+        writeln!(
+            writer,
+            "SCREENSHOT {} {} RGB565",
+            bounds.size.width, bounds.size.height
+        )
+        .map_err(|_| SpiError::TransferFailed)?;
+
+        self.read_rows(&bounds, |row| {
+            for color in row {
+                write!(writer, "{:04X}", color.into_storage())
+                    .map_err(|_| SpiError::TransferFailed)?;
+            }
+            writeln!(writer).map_err(|_| SpiError::TransferFailed)
+        })?;
+
+        writeln!(writer, "END").map_err(|_| SpiError::TransferFailed)
+    }
+
+    /// 逐行读回`area`（须已在屏幕范围内），每行交给`row`处理
+    ///
+    /// 每行单独设置窗口并在一次片选内完成RAMRD，行缓冲只需几百字节。
+    fn read_rows<F>(&mut self, area: &Rectangle, mut row: F) -> Result<(), SpiError>
+    where
+        F: FnMut(&[Rgb565]) -> Result<(), SpiError>,
+    {
+        let width = area.size.width as usize;
+        if width == 0 || area.size.height == 0 {
+            return Ok(());
+        }
+
+        let mut raw = [0u8; ROW_BYTES];
+        let mut colors = [Rgb565::new(0, 0, 0); MAX_ROW];
+        for y in 0..area.size.height as i32 {
+            let line = Rectangle::new(
+                area.top_left + Point::new(0, y),
+                Size::new(area.size.width, 1),
+            );
+            self.set_area(&line)?;
+
+            let raw = &mut raw[..1 + width * 3];
+            self.port.read_command(Command::RamRd, raw)?;
+            // 首字节为哑数据，每个分量在字节的高6位
+            for (color, rgb) in colors.iter_mut().zip(raw[1..].chunks_exact(3)) {
+                *color = Rgb565::new(rgb[0] >> 3, rgb[1] >> 2, rgb[2] >> 3);
+            }
+            row(&colors[..width])?;
+        }
+
+        Ok(())
+    }
+}
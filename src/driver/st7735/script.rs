@@ -0,0 +1,574 @@
+use super::cmd::Command;
+use crate::adapter::spi::ConfigError;
+
+/// 初始化脚本中的一步：命令、参数与之后的延时
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InitStep<'a> {
+    pub command: u8,
+    pub params: &'a [u8],
+    pub delay_ms: u32,
+}
+
+impl<'a> InitStep<'a> {
+    pub const fn new(command: u8, params: &'a [u8], delay_ms: u32) -> Self {
+        Self {
+            command,
+            params,
+            delay_ms,
+        }
+    }
+}
+
+/// 初始化脚本，可替换内置的初始化序列
+///
+/// 两种形式：
+/// - `Table`：[`InitStep`]表，便于手写；
+/// - `Encoded`：压缩字节流，每条为`命令, 参数个数, 参数..., [延时]`。
+///   参数个数的最高位表示其后跟1字节延时（ms），延时255表示500ms，
+///   与Adafruit等驱动中常见的表格式相同（不含开头的命令条数）。
+///
+/// 厂商提供的初始化代码可用[`EncodedScript::parse`]在编译期转换。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitScript<'a> {
+    Table(&'a [InitStep<'a>]),
+    Encoded(&'a [u8]),
+}
+
+impl InitScript<'static> {
+    /// 内置序列，取值与`st7735-lcd`相同
+    ///
+    /// 不含复位、反色、MADCTL与DISPON，它们由驱动按配置另行发送。
+    pub const ST7735R: Self = InitScript::Table(&[
+        InitStep::new(Command::SlpOut.code(), &[], 120),
+        InitStep::new(Command::FrmCtr1.code(), &[0x01, 0x2C, 0x2D], 0),
+        InitStep::new(Command::FrmCtr2.code(), &[0x01, 0x2C, 0x2D], 0),
+        InitStep::new(
+            Command::FrmCtr3.code(),
+            &[0x01, 0x2C, 0x2D, 0x01, 0x2C, 0x2D],
+            0,
+        ),
+        InitStep::new(Command::InvCtr.code(), &[0x07], 0),
+        InitStep::new(Command::PwCtr1.code(), &[0xA2, 0x02, 0x84], 0),
+        InitStep::new(Command::PwCtr2.code(), &[0xC5], 0),
+        InitStep::new(Command::PwCtr3.code(), &[0x0A, 0x00], 0),
+        InitStep::new(Command::PwCtr4.code(), &[0x8A, 0x2A], 0),
+        InitStep::new(Command::PwCtr5.code(), &[0x8A, 0xEE], 0),
+        InitStep::new(Command::VmCtr1.code(), &[0x0E], 0),
+        InitStep::new(Command::Colmod.code(), &[0x05], 0),
+    ]);
+}
+
+impl<'a> InitScript<'a> {
+    /// 逐步遍历，压缩格式有误时提前结束（先用[`InitScript::validate`]检查）
+    pub fn steps(&self) -> Steps<'a> {
+        Steps {
+            script: *self,
+            position: 0,
+        }
+    }
+
+    /// 检查压缩格式是否完整
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if let InitScript::Encoded(bytes) = self {
+            let mut position = 0;
+            while position < bytes.len() {
+                match decode(bytes, position) {
+                    Some((_, next)) => position = next,
+                    None => return Err(ConfigError::MalformedInitScript { offset: position }),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// [`InitScript::steps`]返回的迭代器
+pub struct Steps<'a> {
+    script: InitScript<'a>,
+    position: usize,
+}
+
+impl<'a> Iterator for Steps<'a> {
+    type Item = InitStep<'a>;
+
+    fn next(&mut self) -> Option<InitStep<'a>> {
+        match self.script {
+            InitScript::Table(steps) => {
+                let step = steps.get(self.position)?;
+                self.position += 1;
+                Some(*step)
+            }
+            InitScript::Encoded(bytes) => {
+                let (step, next) = decode(bytes, self.position)?;
+                self.position = next;
+                Some(step)
+            }
+        }
+    }
+}
+
+/// 参数个数字节中的延时标志
+const DELAY_FLAG: u8 = 0x80;
+/// 延时字节的特殊值，表示500ms
+const DELAY_LONG: u8 = 0xFF;
+const DELAY_LONG_MS: u32 = 500;
+
+/// 解码`position`处的一条，返回该步与下一条的位置
+fn decode(bytes: &[u8], position: usize) -> Option<(InitStep<'_>, usize)> {
+    let command = *bytes.get(position)?;
+    let count = *bytes.get(position + 1)?;
+    let params_start = position + 2;
+    let params_end = params_start + (count & !DELAY_FLAG) as usize;
+    let params = bytes.get(params_start..params_end)?;
+
+    let (delay_ms, next) = if count & DELAY_FLAG != 0 {
+        let delay_ms = match *bytes.get(params_end)? {
+            DELAY_LONG => DELAY_LONG_MS,
+            ms => ms as u32,
+        };
+        (delay_ms, params_end + 1)
+    } else {
+        (0, params_end)
+    };
+
+    Some((InitStep::new(command, params, delay_ms), next))
+}
+
+/// 由厂商初始化代码生成的压缩脚本，容量为`N`字节
+///
+/// 放在`static`中即可得到`'static`的[`InitScript`]：
+///
+/// ```ignore
+/// static VENDOR: EncodedScript<256> = EncodedScript::parse(include_str!("panel_init.c"));
+///
+/// let display = St7735Builder::new().init_script(VENDOR.script()).build()?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncodedScript<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> EncodedScript<N> {
+    /// 解析厂商"init code"文本
+    ///
+    /// 按`;`或括号外的换行拆成语句，参数可以分多行写；按函数名识别语句类型（不区分大小写）：
+    /// - 含`delay`/`sleep`/`wait`：延时（ms）；
+    /// - 含`dat`/`param`：数据，括号内可有多个值；
+    /// - 含`cmd`/`com`/`reg`/`index`：命令，其后的值作为参数。
+    ///
+    /// 例如`LCD_WR_REG(0xB1);`、`WriteData(0x2C);`、`Delay(120);`。
+    /// 数值可为十六进制（`0x`前缀）或十进制；`//`与`/* */`注释被忽略，
+    /// 不含数值的语句（如函数头、花括号）被跳过。
+    /// 超过254ms的延时精确拆成多段NOP延时：每满500ms一段（255），余下每段至多254ms。
+    ///
+    /// 在常量上下文中调用时，格式错误或容量不足会变成编译错误。
+    pub const fn parse(text: &str) -> Self {
+        let text = text.as_bytes();
+        let mut encoder = Encoder {
+            bytes: [0; N],
+            len: 0,
+            open: None,
+        };
+
+        let mut position = 0;
+        while position < text.len() {
+            let end = statement_end(text, position);
+            encoder.statement(text, position, end);
+            position = end + 1;
+        }
+
+        Self {
+            bytes: encoder.bytes,
+            len: encoder.len,
+        }
+    }
+
+    /// 压缩字节流
+    pub const fn as_bytes(&self) -> &[u8] {
+        self.bytes.split_at(self.len).0
+    }
+
+    pub const fn script(&self) -> InitScript<'_> {
+        InitScript::Encoded(self.as_bytes())
+    }
+}
+
+/// 解析时的编码状态
+struct Encoder<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+    /// 当前命令的参数个数字节位置，延时或下一条命令后关闭
+    open: Option<usize>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum StatementKind {
+    Command,
+    Data,
+    Delay,
+}
+
+impl<const N: usize> Encoder<N> {
+    const fn statement(&mut self, text: &[u8], start: usize, end: usize) {
+        let mut values = [0u32; 32];
+        let count = numbers(text, start, end, &mut values);
+        if count == 0 {
+            return;
+        }
+
+        let kind = match statement_kind(text, start, end) {
+            Some(kind) => kind,
+            None => panic!("init code: statement with values but no recognised function name"),
+        };
+
+        match kind {
+            StatementKind::Command => {
+                self.command(byte(values[0]));
+                let mut index = 1;
+                while index < count {
+                    self.data(byte(values[index]));
+                    index += 1;
+                }
+            }
+            StatementKind::Data => {
+                let mut index = 0;
+                while index < count {
+                    self.data(byte(values[index]));
+                    index += 1;
+                }
+            }
+            StatementKind::Delay => self.delay(values[0]),
+        }
+    }
+
+    const fn command(&mut self, command: u8) {
+        self.push(command);
+        self.open = Some(self.len);
+        self.push(0);
+    }
+
+    const fn data(&mut self, value: u8) {
+        let Some(count_at) = self.open else {
+            panic!("init code: data without a preceding command");
+        };
+        if self.bytes[count_at] == !DELAY_FLAG {
+            panic!("init code: more than 127 parameters for one command");
+        }
+        self.bytes[count_at] += 1;
+        self.push(value);
+    }
+
+    const fn delay(&mut self, mut ms: u32) {
+        while ms > 0 {
+            // 没有可附加的命令时用NOP承载延时
+            let count_at = match self.open {
+                Some(count_at) => count_at,
+                None => {
+                    self.command(Command::Nop.code());
+                    self.len - 1
+                }
+            };
+            self.bytes[count_at] |= DELAY_FLAG;
+            self.open = None;
+
+            // 0xFF表示500ms，不足500ms的部分按每段至多254ms精确拆分
+            if ms >= DELAY_LONG_MS {
+                self.push(DELAY_LONG);
+                ms -= DELAY_LONG_MS;
+            } else {
+                let chunk = if ms < DELAY_LONG as u32 {
+                    ms
+                } else {
+                    DELAY_LONG as u32 - 1
+                };
+                self.push(chunk as u8);
+                ms -= chunk;
+            }
+        }
+    }
+
+    const fn push(&mut self, value: u8) {
+        if self.len == N {
+            panic!("init code: encoded script exceeds capacity");
+        }
+        self.bytes[self.len] = value;
+        self.len += 1;
+    }
+}
+
+const fn byte(value: u32) -> u8 {
+    if value > 0xFF {
+        panic!("init code: command or data value does not fit in a byte");
+    }
+    value as u8
+}
+
+/// 语句结束位置：`;`或括号外的换行，跳过注释
+const fn statement_end(text: &[u8], start: usize) -> usize {
+    let mut position = start;
+    // 括号内换行不拆分语句，参数可以分多行写
+    let mut depth = 0u32;
+    while position < text.len() {
+        match text[position] {
+            b';' => return position,
+            b'\n' if depth == 0 => return position,
+            b'(' => {
+                depth += 1;
+                position += 1;
+            }
+            b')' => {
+                depth = depth.saturating_sub(1);
+                position += 1;
+            }
+            // 注释中的`;`与括号不影响语句
+            _ => match skip_comment(text, position) {
+                Some(next) => position = next,
+                None => position += 1,
+            },
+        }
+    }
+    position
+}
+
+/// `position`处是注释时返回注释之后的位置（行注释停在换行处）
+const fn skip_comment(text: &[u8], position: usize) -> Option<usize> {
+    if is_block_comment(text, position) {
+        return Some(comment_end(text, position));
+    }
+    if position + 1 < text.len() && text[position] == b'/' && text[position + 1] == b'/' {
+        let mut position = position;
+        while position < text.len() && text[position] != b'\n' {
+            position += 1;
+        }
+        return Some(position);
+    }
+    None
+}
+
+/// 块注释结束后的位置
+const fn comment_end(text: &[u8], start: usize) -> usize {
+    let mut position = start + 2;
+    while position + 1 < text.len() {
+        if text[position] == b'*' && text[position + 1] == b'/' {
+            return position + 2;
+        }
+        position += 1;
+    }
+    text.len()
+}
+
+const fn is_block_comment(text: &[u8], position: usize) -> bool {
+    position + 1 < text.len() && text[position] == b'/' && text[position + 1] == b'*'
+}
+
+/// 由第一个标识符判断语句类型
+const fn statement_kind(text: &[u8], start: usize, end: usize) -> Option<StatementKind> {
+    let mut position = start;
+    while position < end && !is_ident(text[position]) {
+        match skip_comment(text, position) {
+            Some(next) => position = next,
+            None => position += 1,
+        }
+    }
+    let ident_start = position;
+    while position < end && is_ident(text[position]) {
+        position += 1;
+    }
+    let ident = text.split_at(position).0.split_at(ident_start).1;
+
+    if contains(ident, b"delay") || contains(ident, b"sleep") || contains(ident, b"wait") {
+        Some(StatementKind::Delay)
+    } else if contains(ident, b"dat") || contains(ident, b"param") {
+        Some(StatementKind::Data)
+    } else if contains(ident, b"cmd")
+        || contains(ident, b"com")
+        || contains(ident, b"reg")
+        || contains(ident, b"index")
+    {
+        Some(StatementKind::Command)
+    } else {
+        None
+    }
+}
+
+/// 取出括号内（无括号时为整条语句）的数值，返回个数
+const fn numbers(text: &[u8], start: usize, end: usize, values: &mut [u32; 32]) -> usize {
+    let mut position = start;
+    // 函数名中的数字（如`Delay_1ms`）不算参数
+    let mut scan = start;
+    while scan < end {
+        if let Some(next) = skip_comment(text, scan) {
+            scan = next;
+        } else if text[scan] == b'(' {
+            position = scan + 1;
+            break;
+        } else {
+            scan += 1;
+        }
+    }
+
+    let mut count = 0;
+    while position < end {
+        let c = text[position];
+        if let Some(next) = skip_comment(text, position) {
+            position = next;
+        } else if c.is_ascii_digit() && (position == start || !is_ident(text[position - 1])) {
+            if count == values.len() {
+                panic!("init code: too many values in one statement");
+            }
+            let (value, next) = number(text, position, end);
+            values[count] = value;
+            count += 1;
+            position = next;
+        } else {
+            position += 1;
+        }
+    }
+    count
+}
+
+/// 解析一个十进制或`0x`十六进制数
+const fn number(text: &[u8], start: usize, end: usize) -> (u32, usize) {
+    let mut position = start;
+    let mut value: u32 = 0;
+    let hex = position + 1 < end && text[position] == b'0' && (text[position + 1] | 0x20) == b'x';
+    if hex {
+        position += 2;
+        while position < end {
+            let digit = match text[position] {
+                c @ b'0'..=b'9' => c - b'0',
+                c @ b'a'..=b'f' => c - b'a' + 10,
+                c @ b'A'..=b'F' => c - b'A' + 10,
+                _ => break,
+            };
+            value = value * 16 + digit as u32;
+            position += 1;
+        }
+    } else {
+        while position < end && text[position].is_ascii_digit() {
+            value = value * 10 + (text[position] - b'0') as u32;
+            position += 1;
+        }
+    }
+    (value, position)
+}
+
+const fn is_ident(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_'
+}
+
+/// 不区分大小写的子串查找，`needle`须为小写
+const fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    if needle.len() > haystack.len() {
+        return false;
+    }
+    let mut start = 0;
+    while start + needle.len() <= haystack.len() {
+        let mut index = 0;
+        while index < needle.len() && haystack[start + index].to_ascii_lowercase() == needle[index]
+        {
+            index += 1;
+        }
+        if index == needle.len() {
+            return true;
+        }
+        start += 1;
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Write;
+
+    use super::*;
+
+    fn parse_delay(ms: u32) -> EncodedScript<64> {
+        let mut text: heapless::String<64> = heapless::String::new();
+        write!(text, "LCD_WR_REG(0x11);\nDelay_ms({ms});").unwrap();
+        EncodedScript::parse(&text)
+    }
+
+    #[test]
+    fn parses_commands_data_and_comments() {
+        const SCRIPT: EncodedScript<32> = EncodedScript::parse(
+            "void init(void) {
+                LCD_WR_REG(0xB1); // FRMCTR1
+                LCD_WR_DATA(0x01); LCD_WR_DATA(0x2C);
+                /* 延时; 不拆语句 */ Delay(120);
+                WriteComm(0x36, 0xC8);
+            }",
+        );
+        let script = SCRIPT.script();
+        assert_eq!(script.validate(), Ok(()));
+
+        let mut steps = script.steps();
+        assert_eq!(steps.next(), Some(InitStep::new(0xB1, &[0x01, 0x2C], 120)));
+        assert_eq!(steps.next(), Some(InitStep::new(0x36, &[0xC8], 0)));
+        assert_eq!(steps.next(), None);
+    }
+
+    #[test]
+    fn parses_statements_spanning_lines() {
+        const SCRIPT: EncodedScript<32> = EncodedScript::parse(
+            "WriteComm(0x36,
+                0xC8);
+            LCD_WR_REG(0xE0); LCD_WR_DATA(
+                0x02, // 注释中的) 与;
+                /* ( */ 0x1C
+            )
+            Delay_ms(
+                10)",
+        );
+        let script = SCRIPT.script();
+        assert_eq!(script.validate(), Ok(()));
+
+        let mut steps = script.steps();
+        assert_eq!(steps.next(), Some(InitStep::new(0x36, &[0xC8], 0)));
+        assert_eq!(steps.next(), Some(InitStep::new(0xE0, &[0x02, 0x1C], 10)));
+        assert_eq!(steps.next(), None);
+    }
+
+    #[test]
+    fn delays_round_trip_exactly() {
+        for ms in [
+            0, 1, 120, 253, 254, 255, 300, 499, 500, 501, 754, 755, 999, 1000, 1234, 2600,
+        ] {
+            let encoded = parse_delay(ms);
+            let script = encoded.script();
+            assert_eq!(script.validate(), Ok(()));
+
+            let total: u32 = script.steps().map(|step| step.delay_ms).sum();
+            assert_eq!(total, ms, "{ms}ms");
+
+            // 只有第一步是命令本身，其余都是承载延时的NOP
+            let steps = script.steps().count();
+            let long = ms / DELAY_LONG_MS;
+            let short = (ms % DELAY_LONG_MS).div_ceil(DELAY_LONG as u32 - 1);
+            assert_eq!(steps as u32, (long + short).max(1), "{ms}ms");
+            assert!(
+                script
+                    .steps()
+                    .skip(1)
+                    .all(|step| step.command == Command::Nop.code())
+            );
+        }
+    }
+
+    #[test]
+    fn delay_without_command_uses_nop() {
+        const SCRIPT: EncodedScript<8> = EncodedScript::parse("Delay(10);");
+        assert_eq!(SCRIPT.as_bytes(), &[Command::Nop.code(), DELAY_FLAG, 10]);
+    }
+
+    #[test]
+    fn validate_reports_truncated_script() {
+        let script = InitScript::Encoded(&[0x11, 0x80, 0x78, 0xB1, 0x03, 0x01]);
+        assert_eq!(
+            script.validate(),
+            Err(ConfigError::MalformedInitScript { offset: 3 })
+        );
+        assert_eq!(script.steps().count(), 1);
+    }
+}
@@ -0,0 +1,87 @@
+use super::vsync::FrameControl;
+
+/// 伽马校正表（GMCTRP1 / GMCTRN1）
+///
+/// 控制器上电默认的伽马曲线偏平，颜色发灰；按批次调校时整表替换即可。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gamma {
+    /// 正极性（GMCTRP1）
+    pub positive: [u8; 16],
+    /// 负极性（GMCTRN1）
+    pub negative: [u8; 16],
+}
+
+impl Gamma {
+    /// 常见ST7735R模组厂商初始化代码中的伽马表
+    pub const ST7735R: Self = Self {
+        positive: [
+            0x02, 0x1C, 0x07, 0x12, 0x37, 0x32, 0x29, 0x2D, 0x29, 0x25, 0x2B, 0x39, 0x00, 0x01,
+            0x03, 0x10,
+        ],
+        negative: [
+            0x03, 0x1D, 0x07, 0x06, 0x2E, 0x2C, 0x29, 0x2D, 0x2E, 0x2E, 0x37, 0x3F, 0x00, 0x00,
+            0x02, 0x10,
+        ],
+    };
+}
+
+/// 各显示模式的帧率（FRMCTR1-3）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameRates {
+    /// 正常模式（FRMCTR1）
+    pub normal: FrameControl,
+    /// 空闲模式（FRMCTR2）
+    pub idle: FrameControl,
+    /// 局部模式，点反转与列反转各一组（FRMCTR3）
+    pub partial: [FrameControl; 2],
+}
+
+impl FrameRates {
+    /// 控制器初始化序列写入的值
+    pub const DEFAULT: Self = Self {
+        normal: FrameControl::DEFAULT,
+        idle: FrameControl::DEFAULT,
+        partial: [FrameControl::DEFAULT, FrameControl::DEFAULT],
+    };
+}
+
+impl Default for FrameRates {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// 电源控制寄存器（PWCTR1-5、VMCTR1）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PowerControl {
+    /// GVDD电压与AVDD（PWCTR1）
+    pub pwctr1: [u8; 3],
+    /// VGH/VGL电压（PWCTR2）
+    pub pwctr2: u8,
+    /// 正常模式运放电流与升压频率（PWCTR3）
+    pub pwctr3: [u8; 2],
+    /// 空闲模式（PWCTR4）
+    pub pwctr4: [u8; 2],
+    /// 局部模式（PWCTR5）
+    pub pwctr5: [u8; 2],
+    /// VCOM电压（VMCTR1）
+    pub vmctr1: u8,
+}
+
+impl PowerControl {
+    /// 控制器初始化序列写入的值
+    pub const DEFAULT: Self = Self {
+        pwctr1: [0xA2, 0x02, 0x84],
+        pwctr2: 0xC5,
+        pwctr3: [0x0A, 0x00],
+        pwctr4: [0x8A, 0x2A],
+        pwctr5: [0x8A, 0xEE],
+        vmctr1: 0x0E,
+    };
+}
+
+impl Default for PowerControl {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
@@ -0,0 +1,123 @@
+use ecos_ssc1::Timer;
+use embedded_hal::digital::InputPin;
+
+use crate::adapter::gpio::EbdHalGpio;
+use crate::adapter::spi::SpiError;
+
+/// 防撕裂同步方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrameSync {
+    /// 不同步，刷新立即返回
+    #[default]
+    Off,
+    /// 等待TE引脚上的消隐上升沿（参数为排针号），初始化时发送TEON
+    Te(u32),
+    /// 未接TE时按帧周期节流，两次刷新的间隔不小于一帧
+    Paced,
+}
+
+/// 帧率控制参数（FRMCTR1，正常模式）
+///
+/// 帧率 = 850kHz / ((RTNA × 2 + 40) × (行数 + FPA + BPA + 2))
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameControl {
+    pub rtna: u8,
+    pub fpa: u8,
+    pub bpa: u8,
+}
+
+/// 内部振荡器频率
+const OSC_HZ: u64 = 850_000;
+
+impl FrameControl {
+    /// 控制器初始化序列写入的值，128x160约80Hz
+    pub const DEFAULT: Self = Self {
+        rtna: 0x01,
+        fpa: 0x2C,
+        bpa: 0x2D,
+    };
+
+    /// `lines`行扫描一帧所需的时间（微秒）
+    pub fn frame_period_us(self, lines: u16) -> u32 {
+        let clocks_per_line = (self.rtna & 0x0F) as u64 * 2 + 40;
+        let line_count = lines as u64 + (self.fpa & 0x3F) as u64 + (self.bpa & 0x3F) as u64 + 2;
+        (clocks_per_line * line_count * 1_000_000 / OSC_HZ) as u32
+    }
+
+    /// 寄存器参数 (RTNA, FPA, BPA)
+    pub fn params(self) -> [u8; 3] {
+        [self.rtna, self.fpa, self.bpa]
+    }
+}
+
+impl Default for FrameControl {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// TE轮询间隔（微秒）
+const TE_POLL_US: u32 = 10;
+
+/// 帧同步器：在开始传输像素前等待合适的时机
+pub(crate) struct FrameSyncer {
+    sync: FrameSync,
+    period_us: u32,
+    /// 上次刷新时的系统tick（毫秒）
+    last_tick: Option<u32>,
+}
+
+impl FrameSyncer {
+    pub(crate) fn new(sync: FrameSync, period_us: u32) -> Self {
+        Self {
+            sync,
+            period_us,
+            last_tick: None,
+        }
+    }
+
+    /// 等到可以开始传输下一帧
+    pub(crate) fn wait(&mut self) -> Result<(), SpiError> {
+        match self.sync {
+            FrameSync::Off => Ok(()),
+            FrameSync::Te(pin) => self.wait_te(pin),
+            FrameSync::Paced => {
+                self.wait_paced();
+                Ok(())
+            }
+        }
+    }
+
+    /// 等待TE上升沿，即垂直消隐开始
+    ///
+    /// 若正处于消隐期，先等它结束，避免在消隐尾部开始传输。
+    /// 超时取两帧，TE未输出时返回[`SpiError::Timeout`]而不是卡死。
+    fn wait_te(&mut self, pin: u32) -> Result<(), SpiError> {
+        let mut te = EbdHalGpio::new(pin);
+        let timeout = self.period_us.saturating_mul(2);
+        let mut waited = 0;
+
+        for level in [true, false] {
+            while te.is_high().map_err(|_| SpiError::GpioError)? == level {
+                if waited >= timeout {
+                    return Err(SpiError::Timeout);
+                }
+                Timer::delay_us(TE_POLL_US);
+                waited += TE_POLL_US;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 距上次刷新不足一帧时补足剩余时间
+    fn wait_paced(&mut self) {
+        if let Some(last) = self.last_tick {
+            let elapsed_us = Timer::get_tick().wrapping_sub(last).saturating_mul(1_000);
+            if elapsed_us < self.period_us {
+                Timer::delay_us(self.period_us - elapsed_us);
+            }
+        }
+        self.last_tick = Some(Timer::get_tick());
+    }
+}
@@ -0,0 +1,249 @@
+use core::convert::Infallible;
+
+use embedded_graphics_core::{
+    Drawable, Pixel,
+    draw_target::DrawTarget,
+    geometry::{Dimensions, Point, Size},
+    pixelcolor::{Rgb565, RgbColor},
+    primitives::Rectangle,
+};
+use heapless::Vec;
+
+use crate::driver::panel::WindowWrite;
+
+/// 可按条带重放的绘制项
+///
+/// 任何带包围盒的RGB565 [`Drawable`]（图元、文字、图片）都自动实现；
+/// 与当前条带不相交的项直接跳过。
+pub trait BandDraw {
+    /// 在条带上绘制
+    fn draw_band(&self, band: &mut Band<'_>);
+
+    /// 包围盒，用于按条带剔除
+    fn band_bounds(&self) -> Rectangle;
+}
+
+impl<T> BandDraw for T
+where
+    T: Drawable<Color = Rgb565> + Dimensions,
+{
+    fn draw_band(&self, band: &mut Band<'_>) {
+        let _ = self.draw(band);
+    }
+
+    fn band_bounds(&self) -> Rectangle {
+        self.bounding_box()
+    }
+}
+
+/// 录制的绘制列表，按加入顺序重放（后加入的在上层）
+pub struct DrawList<'a, const N: usize> {
+    items: Vec<&'a dyn BandDraw, N>,
+}
+
+impl<'a, const N: usize> DrawList<'a, N> {
+    pub const fn new() -> Self {
+        Self { items: Vec::new() }
+    }
+
+    /// 加入一项，列表已满时原样返回
+    pub fn push(&mut self, item: &'a dyn BandDraw) -> Result<(), &'a dyn BandDraw> {
+        self.items.push(item)
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// 在条带上重放
+    pub fn replay(&self, band: &mut Band<'_>) {
+        let area = band.area();
+        for item in &self.items {
+            let visible = item.band_bounds().intersection(&area);
+            if visible.size.width > 0 && visible.size.height > 0 {
+                item.draw_band(band);
+            }
+        }
+    }
+}
+
+impl<const N: usize> Default for DrawList<'_, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 一个条带：屏幕上的若干整行，坐标仍为屏幕坐标，范围外的绘制被裁掉
+pub struct Band<'a> {
+    pixels: &'a mut [Rgb565],
+    area: Rectangle,
+    /// 画到过的像素，合成无颜色键的层时用来区分透明处
+    coverage: Option<&'a mut [bool]>,
+}
+
+impl<'a> Band<'a> {
+    /// `pixels`按行优先覆盖`area`，长度须为`area`的像素数
+    pub(crate) fn new(pixels: &'a mut [Rgb565], area: Rectangle) -> Self {
+        Self {
+            pixels,
+            area,
+            coverage: None,
+        }
+    }
+
+    /// 同时把画到的像素在`coverage`（与`pixels`等长，调用方先清零）中置位
+    pub(crate) fn with_coverage(
+        pixels: &'a mut [Rgb565],
+        coverage: &'a mut [bool],
+        area: Rectangle,
+    ) -> Self {
+        Self {
+            pixels,
+            area,
+            coverage: Some(coverage),
+        }
+    }
+
+    /// 条带在屏幕上的范围
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+
+    /// 条带的像素，按行优先
+    pub fn pixels(&self) -> &[Rgb565] {
+        self.pixels
+    }
+
+    /// 从下标`start`起写入连续像素
+    pub(crate) fn copy_from(&mut self, start: usize, pixels: &[Rgb565]) {
+        let end = start + pixels.len();
+        self.pixels[start..end].copy_from_slice(pixels);
+        if let Some(coverage) = &mut self.coverage {
+            coverage[start..end].fill(true);
+        }
+    }
+
+    fn index(&self, point: Point) -> usize {
+        let offset = point - self.area.top_left;
+        offset.y as usize * self.area.size.width as usize + offset.x as usize
+    }
+}
+
+impl Dimensions for Band<'_> {
+    fn bounding_box(&self) -> Rectangle {
+        self.area
+    }
+}
+
+impl DrawTarget for Band<'_> {
+    type Color = Rgb565;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if self.area.contains(point) {
+                let index = self.index(point);
+                self.pixels[index] = color;
+                if let Some(coverage) = &mut self.coverage {
+                    coverage[index] = true;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.area);
+        if area.size.width == 0 || area.size.height == 0 {
+            return Ok(());
+        }
+
+        let width = area.size.width as usize;
+        for y in 0..area.size.height as i32 {
+            let start = self.index(area.top_left + Point::new(0, y));
+            self.pixels[start..start + width].fill(color);
+            if let Some(coverage) = &mut self.coverage {
+                coverage[start..start + width].fill(true);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 条带渲染器：不需要整屏帧缓冲
+///
+/// 把区域按`LINES`行切成条带，每个条带先填背景，重放整个场景（裁剪到条带内），
+/// 再按窗口发给显示驱动。内存只需`W * LINES * 2`字节，
+/// 代价是场景要重放`高度 / LINES`次。
+pub struct BandRenderer<const W: usize, const LINES: usize> {
+    buffer: [[Rgb565; W]; LINES],
+    /// 每个条带的底色
+    pub background: Rgb565,
+}
+
+impl<const W: usize, const LINES: usize> BandRenderer<W, LINES> {
+    pub const fn new(background: Rgb565) -> Self {
+        const { assert!(W > 0 && LINES > 0) };
+        Self {
+            buffer: [[Rgb565::BLACK; W]; LINES],
+            background,
+        }
+    }
+
+    /// 渲染`area`（宽度不超过`W`，超出部分被裁掉），每个条带调用一次`scene`
+    pub fn render<D, F>(
+        &mut self,
+        display: &mut D,
+        area: &Rectangle,
+        mut scene: F,
+    ) -> Result<(), D::Error>
+    where
+        D: WindowWrite,
+        F: FnMut(&mut Band<'_>),
+    {
+        let width = area.size.width.min(W as u32);
+        let mut y = 0;
+        while y < area.size.height {
+            let lines = (area.size.height - y).min(LINES as u32);
+            let band_area = Rectangle::new(
+                area.top_left + Point::new(0, y as i32),
+                Size::new(width, lines),
+            );
+
+            let len = (width * lines) as usize;
+            let pixels = &mut self.buffer.as_flattened_mut()[..len];
+            pixels.fill(self.background);
+
+            let mut band = Band::new(pixels, band_area);
+            scene(&mut band);
+            display.write_window(&band_area, band.pixels.iter().copied())?;
+
+            y += lines;
+        }
+        Ok(())
+    }
+
+    /// 按条带重放绘制列表
+    pub fn render_list<D, const N: usize>(
+        &mut self,
+        display: &mut D,
+        area: &Rectangle,
+        list: &DrawList<'_, N>,
+    ) -> Result<(), D::Error>
+    where
+        D: WindowWrite,
+    {
+        self.render(display, area, |band| list.replay(band))
+    }
+}
@@ -0,0 +1,542 @@
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{Point, Size},
+    pixelcolor::{Rgb565, RgbColor},
+    primitives::Rectangle,
+};
+use heapless::Vec;
+
+use super::band::Band;
+use super::dirty::DirtyRects;
+use super::framebuffer::Framebuffer;
+
+/// 层的内容来源：帧缓冲或绘制回调
+///
+/// 合成时按层坐标请求一块区域画到[`Band`]中，区域之外的绘制被裁掉。
+/// 闭包`|band: &mut Band<'_>| { ... }`即可作为回调层，每次合成都会重新调用。
+/// 无颜色键的层中没有画到的像素是透明的。
+pub trait LayerSource {
+    /// 把层坐标下的`band.area()`画到`band`
+    fn render(&mut self, band: &mut Band<'_>);
+
+    /// `render`是否总会画满`area`（层坐标），默认否
+    fn covers(&self, area: &Rectangle) -> bool {
+        let _ = area;
+        false
+    }
+
+    /// 取出自上次合成以来内容改变的区域（层坐标），默认没有
+    fn take_changes(&mut self, changes: &mut DirtyRects) {
+        let _ = changes;
+    }
+}
+
+/// 帧缓冲层：按行拷贝，绘制产生的脏区在合成时自动取出
+impl<const W: usize, const H: usize> LayerSource for Framebuffer<W, H> {
+    fn render(&mut self, band: &mut Band<'_>) {
+        let area = band.area();
+        let visible = area.intersection(&self.bounds());
+        if visible.size.width == 0 || visible.size.height == 0 {
+            return;
+        }
+
+        let stride = area.size.width as usize;
+        let offset = visible.top_left - area.top_left;
+        for (index, row) in self.area_rows(&visible).enumerate() {
+            let start = (offset.y as usize + index) * stride + offset.x as usize;
+            band.copy_from(start, row);
+        }
+    }
+
+    fn covers(&self, area: &Rectangle) -> bool {
+        self.bounds().intersection(area) == *area
+    }
+
+    fn take_changes(&mut self, changes: &mut DirtyRects) {
+        for rect in self.dirty_rects() {
+            changes.add(*rect);
+        }
+        self.clear_dirty();
+    }
+}
+
+impl<F: FnMut(&mut Band<'_>)> LayerSource for F {
+    fn render(&mut self, band: &mut Band<'_>) {
+        self(band)
+    }
+}
+
+/// 层的位置与合成参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layer {
+    /// 在输出上的范围
+    pub area: Rectangle,
+    /// 越大越靠上，相同时后加入的在上
+    pub z: i16,
+    /// 不透明度，255不透明，0不可见
+    pub opacity: u8,
+    /// 等于此颜色的像素透明
+    pub color_key: Option<Rgb565>,
+    pub visible: bool,
+}
+
+impl Layer {
+    pub const fn new(area: Rectangle) -> Self {
+        Self {
+            area,
+            z: 0,
+            opacity: 255,
+            color_key: None,
+            visible: true,
+        }
+    }
+
+    pub const fn z(mut self, z: i16) -> Self {
+        self.z = z;
+        self
+    }
+
+    pub const fn opacity(mut self, opacity: u8) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    pub const fn color_key(mut self, key: Rgb565) -> Self {
+        self.color_key = Some(key);
+        self
+    }
+
+    pub const fn hidden(mut self) -> Self {
+        self.visible = false;
+        self
+    }
+
+    /// 不透明且无颜色键：直接覆盖下层
+    fn is_opaque(&self) -> bool {
+        self.opacity == 255 && self.color_key.is_none()
+    }
+
+    fn is_shown(&self) -> bool {
+        self.visible && self.opacity > 0
+    }
+}
+
+/// 层编号，也是[`Compositor::compose`]中`sources`的下标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LayerId(u8);
+
+impl LayerId {
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// 层合成器：最多`N`层，按z序把可见层混合到输出
+///
+/// 只合成脏区：层的移动、显隐、参数变化以及内容改动（帧缓冲层自动取出）。
+/// 脏区按`W`宽、`LINES`行的条带处理，不透明层整行拷贝，
+/// 完全盖住条带的不透明层以下的层直接跳过。
+///
+/// 内容不存放在合成器中，每次合成时按层编号传入，两次合成之间可以自由绘制各层的帧缓冲。
+pub struct Compositor<const N: usize, const W: usize, const LINES: usize> {
+    layers: Vec<Layer, N>,
+    dirty: DirtyRects,
+    /// 下次合成整个输出
+    full: bool,
+    /// 所有层之下的底色
+    background: Rgb565,
+    /// 当前条带的合成结果
+    output: [[Rgb565; W]; LINES],
+    /// 单层渲染用
+    scratch: [[Rgb565; W]; LINES],
+    /// 单层渲染时画到的像素
+    coverage: [[bool; W]; LINES],
+}
+
+impl<const N: usize, const W: usize, const LINES: usize> Compositor<N, W, LINES> {
+    /// 第一次合成时整个输出都会合成
+    pub const fn new(background: Rgb565) -> Self {
+        const { assert!(N <= 256 && W > 0 && LINES > 0) };
+        Self {
+            layers: Vec::new(),
+            dirty: DirtyRects::new(),
+            full: true,
+            background,
+            output: [[Rgb565::BLACK; W]; LINES],
+            scratch: [[Rgb565::BLACK; W]; LINES],
+            coverage: [[false; W]; LINES],
+        }
+    }
+
+    /// 加入一层，已满时原样返回
+    pub fn add(&mut self, layer: Layer) -> Result<LayerId, Layer> {
+        let id = LayerId(self.layers.len() as u8);
+        self.layers.push(layer)?;
+        self.mark_layer(&layer);
+        Ok(id)
+    }
+
+    pub fn layer(&self, id: LayerId) -> &Layer {
+        &self.layers[id.index()]
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// 修改层参数，新旧范围都记为脏区
+    pub fn update(&mut self, id: LayerId, f: impl FnOnce(&mut Layer)) {
+        let old = self.layers[id.index()];
+        let layer = &mut self.layers[id.index()];
+        f(layer);
+        let new = *layer;
+        if new != old {
+            self.mark_layer(&old);
+            self.mark_layer(&new);
+        }
+    }
+
+    pub fn set_position(&mut self, id: LayerId, position: Point) {
+        self.update(id, |layer| layer.area.top_left = position);
+    }
+
+    pub fn set_z(&mut self, id: LayerId, z: i16) {
+        self.update(id, |layer| layer.z = z);
+    }
+
+    pub fn set_opacity(&mut self, id: LayerId, opacity: u8) {
+        self.update(id, |layer| layer.opacity = opacity);
+    }
+
+    pub fn set_color_key(&mut self, id: LayerId, key: Option<Rgb565>) {
+        self.update(id, |layer| layer.color_key = key);
+    }
+
+    pub fn set_visible(&mut self, id: LayerId, visible: bool) {
+        self.update(id, |layer| layer.visible = visible);
+    }
+
+    pub fn set_background(&mut self, color: Rgb565) {
+        if color != self.background {
+            self.background = color;
+            self.invalidate();
+        }
+    }
+
+    /// 标记输出上的区域需要重新合成
+    pub fn mark_dirty(&mut self, area: &Rectangle) {
+        self.dirty.add(*area);
+    }
+
+    /// 标记层内容改变的区域（层坐标），回调层内容变化时使用
+    pub fn mark_layer_dirty(&mut self, id: LayerId, area: &Rectangle) {
+        let layer = self.layers[id.index()];
+        if layer.is_shown() {
+            let area = Rectangle::new(area.top_left + layer.area.top_left, area.size);
+            self.dirty.add(area.intersection(&layer.area));
+        }
+    }
+
+    /// 层覆盖的范围需要重新合成
+    fn mark_layer(&mut self, layer: &Layer) {
+        if layer.is_shown() {
+            self.dirty.add(layer.area);
+        }
+    }
+
+    /// 下次合成时重新合成整个输出
+    pub fn invalidate(&mut self) {
+        self.dirty.clear();
+        self.full = true;
+    }
+
+    /// 合成脏区并写入`output`，`sources[i]`是第`i`层的内容
+    ///
+    /// 缺少内容的层按不存在处理。输出为[`Framebuffer`]时只有真正改变的像素记入它的脏区。
+    /// 出错时未写入的区域保留，下次合成会重试。
+    pub fn compose<D>(
+        &mut self,
+        output: &mut D,
+        sources: &mut [&mut dyn LayerSource],
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let mut changes = DirtyRects::new();
+        for (layer, source) in self.layers.iter().zip(sources.iter_mut()) {
+            source.take_changes(&mut changes);
+            if layer.is_shown() {
+                for rect in changes.rects() {
+                    let rect = Rectangle::new(rect.top_left + layer.area.top_left, rect.size);
+                    self.dirty.add(rect.intersection(&layer.area));
+                }
+            }
+            changes.clear();
+        }
+
+        // 按z序从下到上，z相同时保持加入顺序
+        let mut order: Vec<usize, N> = (0..self.layers.len().min(sources.len()))
+            .filter(|&index| self.layers[index].is_shown())
+            .collect();
+        order.sort_unstable_by_key(|&index| (self.layers[index].z, index));
+
+        let bounds = output.bounding_box();
+        if core::mem::take(&mut self.full) {
+            self.dirty.clear();
+            self.dirty.add(bounds);
+        }
+        let rects = self.dirty.take();
+        for (index, rect) in rects.iter().enumerate() {
+            let area = rect.intersection(&bounds);
+            if let Err(error) = self.compose_area(output, sources, &order, &area) {
+                for rect in &rects[index..] {
+                    self.dirty.add(*rect);
+                }
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    /// 按条带合成一个矩形
+    fn compose_area<D>(
+        &mut self,
+        output: &mut D,
+        sources: &mut [&mut dyn LayerSource],
+        order: &[usize],
+        area: &Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let mut y = 0;
+        while y < area.size.height {
+            let lines = (area.size.height - y).min(LINES as u32);
+            let mut x = 0;
+            while x < area.size.width {
+                let width = (area.size.width - x).min(W as u32);
+                let band = Rectangle::new(
+                    area.top_left + Point::new(x as i32, y as i32),
+                    Size::new(width, lines),
+                );
+                self.compose_band(sources, order, &band);
+
+                let len = (width * lines) as usize;
+                let pixels = &self.output.as_flattened()[..len];
+                output.fill_contiguous(&band, pixels.iter().copied())?;
+                x += width;
+            }
+            y += lines;
+        }
+        Ok(())
+    }
+
+    /// 合成一个条带到`self.output`，按行优先、宽度为条带宽度
+    fn compose_band(
+        &mut self,
+        sources: &mut [&mut dyn LayerSource],
+        order: &[usize],
+        band: &Rectangle,
+    ) {
+        let stride = band.size.width as usize;
+        let len = stride * band.size.height as usize;
+
+        // 最上面一个完全盖住条带的不透明层以下都不必合成
+        let first = order
+            .iter()
+            .rposition(|&index| {
+                let layer = &self.layers[index];
+                let local = Rectangle::new(band.top_left - layer.area.top_left, band.size);
+                layer.is_opaque()
+                    && layer.area.intersection(band) == *band
+                    && sources[index].covers(&local)
+            })
+            .unwrap_or_else(|| {
+                self.output.as_flattened_mut()[..len].fill(self.background);
+                0
+            });
+
+        for &index in &order[first..] {
+            let layer = self.layers[index];
+            let visible = layer.area.intersection(band);
+            if visible.size.width == 0 || visible.size.height == 0 {
+                continue;
+            }
+
+            let width = visible.size.width as usize;
+            let pixels = width * visible.size.height as usize;
+            let scratch = &mut self.scratch.as_flattened_mut()[..pixels];
+            let local = Rectangle::new(visible.top_left - layer.area.top_left, visible.size);
+            let source = &mut sources[index];
+            // 有颜色键时以键色铺底，否则记录画到的像素，未画到处都透明
+            let coverage = match layer.color_key {
+                Some(key) => {
+                    scratch.fill(key);
+                    source.render(&mut Band::new(scratch, local));
+                    None
+                }
+                None if source.covers(&local) => {
+                    source.render(&mut Band::new(scratch, local));
+                    None
+                }
+                None => {
+                    let coverage = &mut self.coverage.as_flattened_mut()[..pixels];
+                    coverage.fill(false);
+                    source.render(&mut Band::with_coverage(scratch, coverage, local));
+                    Some(&*coverage)
+                }
+            };
+
+            let offset = visible.top_left - band.top_left;
+            let output = self.output.as_flattened_mut();
+            for (row, src) in scratch.chunks_exact(width).enumerate() {
+                let start = (offset.y as usize + row) * stride + offset.x as usize;
+                let dst = &mut output[start..start + width];
+                let covered = coverage.map(|coverage| &coverage[row * width..][..width]);
+                if layer.is_opaque() && covered.is_none() {
+                    dst.copy_from_slice(src);
+                } else {
+                    blend_row(dst, src, covered, &layer);
+                }
+            }
+        }
+    }
+}
+
+/// 按颜色键与不透明度把`src`混合到`dst`，`covered`中为假的像素跳过
+fn blend_row(dst: &mut [Rgb565], src: &[Rgb565], covered: Option<&[bool]>, layer: &Layer) {
+    for (index, (dst, &src)) in dst.iter_mut().zip(src).enumerate() {
+        if layer.color_key == Some(src) || covered.is_some_and(|covered| !covered[index]) {
+            continue;
+        }
+        *dst = if layer.opacity == 255 {
+            src
+        } else {
+            blend(src, *dst, layer.opacity)
+        };
+    }
+}
+
+/// `src`以`alpha`/255的不透明度盖在`dst`上
+fn blend(src: Rgb565, dst: Rgb565, alpha: u8) -> Rgb565 {
+    // 映射到0..=256，便于用移位代替除法
+    let alpha = alpha as u16 + (alpha >> 7) as u16;
+    let mix = |s: u8, d: u8| ((s as u16 * alpha + d as u16 * (256 - alpha)) >> 8) as u8;
+    Rgb565::new(
+        mix(src.r(), dst.r()),
+        mix(src.g(), dst.g()),
+        mix(src.b(), dst.b()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLORS: [Rgb565; 5] = [
+        Rgb565::BLACK,
+        Rgb565::WHITE,
+        Rgb565::RED,
+        Rgb565::new(10, 40, 20),
+        Rgb565::new(31, 1, 17),
+    ];
+
+    #[test]
+    fn blend_extremes() {
+        for src in COLORS {
+            for dst in COLORS {
+                assert_eq!(blend(src, dst, 255), src);
+                assert_eq!(blend(src, dst, 0), dst);
+            }
+        }
+    }
+
+    #[test]
+    fn blend_same_color_is_unchanged() {
+        for color in COLORS {
+            for alpha in 0..=255 {
+                assert_eq!(blend(color, color, alpha), color);
+            }
+        }
+    }
+
+    #[test]
+    fn blend_half_and_range() {
+        assert_eq!(
+            blend(Rgb565::WHITE, Rgb565::BLACK, 128),
+            Rgb565::new(15, 31, 15)
+        );
+        assert_eq!(
+            blend(Rgb565::BLACK, Rgb565::WHITE, 128),
+            Rgb565::new(15, 31, 15)
+        );
+
+        let between = |value: u8, a: u8, b: u8| a.min(b) <= value && value <= a.max(b);
+        for src in COLORS {
+            for dst in COLORS {
+                for alpha in (0..=255).step_by(17) {
+                    let mixed = blend(src, dst, alpha);
+                    assert!(between(mixed.r(), src.r(), dst.r()));
+                    assert!(between(mixed.g(), src.g(), dst.g()));
+                    assert!(between(mixed.b(), src.b(), dst.b()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn blend_row_skips_color_key() {
+        let key = Rgb565::new(31, 0, 31);
+        let layer = Layer::new(Rectangle::zero()).color_key(key);
+        let mut dst = [Rgb565::BLACK; 3];
+        blend_row(&mut dst, &[Rgb565::WHITE, key, Rgb565::RED], None, &layer);
+        assert_eq!(dst, [Rgb565::WHITE, Rgb565::BLACK, Rgb565::RED]);
+
+        let layer = layer.opacity(128);
+        let mut dst = [Rgb565::BLACK; 2];
+        blend_row(&mut dst, &[Rgb565::WHITE, key], None, &layer);
+        assert_eq!(
+            dst,
+            [blend(Rgb565::WHITE, Rgb565::BLACK, 128), Rgb565::BLACK]
+        );
+    }
+
+    #[test]
+    fn keyless_callback_layer_is_transparent_where_not_drawn() {
+        let mut below = Framebuffer::<8, 8>::new();
+        below.fill_solid(&below.bounds(), Rgb565::BLUE).unwrap();
+        let mut above = |band: &mut Band<'_>| {
+            // 只画左上角2x2
+            let area = Rectangle::new(Point::zero(), Size::new(2, 2));
+            band.fill_solid(&area, Rgb565::WHITE).unwrap();
+        };
+
+        let mut compositor = Compositor::<2, 4, 3>::new(Rgb565::BLACK);
+        let bounds = Rectangle::new(Point::zero(), Size::new(8, 8));
+        compositor.add(Layer::new(bounds)).unwrap();
+        compositor
+            .add(Layer::new(Rectangle::new(Point::new(1, 1), Size::new(6, 6))).opacity(128))
+            .unwrap();
+
+        let mut output = Framebuffer::<8, 8>::new();
+        compositor
+            .compose(&mut output, &mut [&mut below, &mut above])
+            .unwrap();
+
+        let mixed = blend(Rgb565::WHITE, Rgb565::BLUE, 128);
+        for y in 0..8 {
+            for x in 0..8 {
+                let expected = if (1..3).contains(&x) && (1..3).contains(&y) {
+                    mixed
+                } else {
+                    Rgb565::BLUE
+                };
+                assert_eq!(output.row(y)[x], expected, "({x}, {y})");
+            }
+        }
+    }
+}
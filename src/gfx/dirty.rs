@@ -0,0 +1,329 @@
+use embedded_graphics_core::{
+    geometry::{Point, Size},
+    primitives::Rectangle,
+};
+use heapless::Vec;
+
+/// 最多同时记录的脏矩形数
+pub const MAX_DIRTY_RECTS: usize = 8;
+
+/// 一次加入中合并/拆分的最多步数
+const MAX_STEPS: usize = 32;
+
+/// 每个窗口的额外开销（CASET/RASET/RAMWR约11字节），折合为像素数
+const WINDOW_COST: u32 = 8;
+
+/// 脏矩形集合
+///
+/// 新矩形加入时：
+/// - 被已有矩形包含则忽略，包含已有矩形则替换；
+/// - 与某个矩形合并后多出的面积不超过约1/3（外加一个窗口的开销）时合并；
+/// - 否则与重叠的矩形相减，拆成不重叠的几块分别加入；
+/// - 数量已满时并入使外包面积增长最少的那个。
+///
+/// 因此集合内的矩形互不重叠，刷新时不会重复发送像素。
+#[derive(Debug, Clone)]
+pub struct DirtyRects {
+    rects: Vec<Rectangle, MAX_DIRTY_RECTS>,
+}
+
+impl DirtyRects {
+    pub const fn new() -> Self {
+        Self { rects: Vec::new() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.rects.clear();
+    }
+
+    /// 当前的脏矩形
+    pub fn rects(&self) -> &[Rectangle] {
+        &self.rects
+    }
+
+    /// 取出全部脏矩形并清空
+    pub fn take(&mut self) -> Vec<Rectangle, MAX_DIRTY_RECTS> {
+        core::mem::take(&mut self.rects)
+    }
+
+    /// 加入一个矩形
+    pub fn add(&mut self, rect: Rectangle) {
+        let mut pending: Vec<Rectangle, 16> = Vec::new();
+        let _ = pending.push(rect);
+        let mut steps = 0;
+
+        while let Some(rect) = pending.pop() {
+            if is_empty(&rect) || self.rects.iter().any(|r| contains(r, &rect)) {
+                continue;
+            }
+            self.rects.retain(|r| !contains(&rect, r));
+
+            // 合并与拆分可能互相引出新的矩形，超过步数后不再拆分
+            steps += 1;
+            if steps > MAX_STEPS {
+                self.absorb(rect);
+                continue;
+            }
+
+            // 合并代价小时直接合并，合并后的矩形重新加入以便继续合并
+            if let Some(index) = self.rects.iter().position(|r| worth_merging(r, &rect)) {
+                let merged = union(&self.rects.swap_remove(index), &rect);
+                let _ = pending.push(merged);
+                continue;
+            }
+
+            // 与已有矩形重叠时只加入不重叠的部分
+            if let Some(index) = self.rects.iter().position(|r| overlaps(r, &rect)) {
+                let pieces = subtract(&rect, &self.rects[index]);
+                if pending.len() + pieces.len() <= pending.capacity() {
+                    pending.extend(pieces);
+                } else {
+                    self.absorb(rect);
+                }
+                continue;
+            }
+
+            if let Err(rect) = self.rects.push(rect) {
+                self.absorb(rect);
+            }
+        }
+    }
+
+    /// 只合并不拆分地加入：吞并所有重叠的矩形，已满时并入增长最少的那个
+    fn absorb(&mut self, mut rect: Rectangle) {
+        loop {
+            if let Some(index) = self.rects.iter().position(|r| overlaps(r, &rect)) {
+                rect = union(&self.rects.swap_remove(index), &rect);
+                continue;
+            }
+            match self.rects.push(rect) {
+                Ok(()) => return,
+                Err(full) => {
+                    let index = (0..self.rects.len())
+                        .min_by_key(|&i| area(&union(&self.rects[i], &full)) - area(&self.rects[i]))
+                        .unwrap_or(0);
+                    rect = union(&self.rects.swap_remove(index), &full);
+                }
+            }
+        }
+    }
+}
+
+impl Default for DirtyRects {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_empty(rect: &Rectangle) -> bool {
+    rect.size.width == 0 || rect.size.height == 0
+}
+
+fn area(rect: &Rectangle) -> u32 {
+    rect.size.width * rect.size.height
+}
+
+fn contains(outer: &Rectangle, inner: &Rectangle) -> bool {
+    outer.intersection(inner) == *inner
+}
+
+fn overlaps(a: &Rectangle, b: &Rectangle) -> bool {
+    !is_empty(&a.intersection(b))
+}
+
+/// 外包矩形
+pub(crate) fn union(a: &Rectangle, b: &Rectangle) -> Rectangle {
+    if is_empty(a) {
+        return *b;
+    }
+    if is_empty(b) {
+        return *a;
+    }
+    let top_left = Point::new(
+        a.top_left.x.min(b.top_left.x),
+        a.top_left.y.min(b.top_left.y),
+    );
+    let bottom_right = Point::new(
+        (a.top_left.x + a.size.width as i32).max(b.top_left.x + b.size.width as i32),
+        (a.top_left.y + a.size.height as i32).max(b.top_left.y + b.size.height as i32),
+    );
+    Rectangle::with_corners(top_left, bottom_right - Point::new(1, 1))
+}
+
+fn worth_merging(a: &Rectangle, b: &Rectangle) -> bool {
+    let covered = area(a) + area(b) - area(&a.intersection(b));
+    let merged = area(&union(a, b));
+    merged * 3 <= covered * 4 + WINDOW_COST * 3
+}
+
+/// `rect`减去`hole`：上、下两条整宽，中间左、右两块
+fn subtract(rect: &Rectangle, hole: &Rectangle) -> Vec<Rectangle, 4> {
+    let mut pieces = Vec::new();
+    let hole = rect.intersection(hole);
+    if is_empty(&hole) {
+        let _ = pieces.push(*rect);
+        return pieces;
+    }
+
+    let left = rect.top_left.x;
+    let top = rect.top_left.y;
+    let right = left + rect.size.width as i32;
+    let bottom = top + rect.size.height as i32;
+    let hole_left = hole.top_left.x;
+    let hole_top = hole.top_left.y;
+    let hole_right = hole_left + hole.size.width as i32;
+    let hole_bottom = hole_top + hole.size.height as i32;
+
+    let candidates = [
+        (left, top, right, hole_top),
+        (left, hole_bottom, right, bottom),
+        (left, hole_top, hole_left, hole_bottom),
+        (hole_right, hole_top, right, hole_bottom),
+    ];
+    for (x0, y0, x1, y1) in candidates {
+        if x1 > x0 && y1 > y0 {
+            let piece = Rectangle::new(
+                Point::new(x0, y0),
+                Size::new((x1 - x0) as u32, (y1 - y0) as u32),
+            );
+            let _ = pieces.push(piece);
+        }
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> Rectangle {
+        Rectangle::new(Point::new(x, y), Size::new(width, height))
+    }
+
+    fn covers(rects: &[Rectangle], target: &Rectangle) -> bool {
+        let x0 = target.top_left.x;
+        let y0 = target.top_left.y;
+        (y0..y0 + target.size.height as i32).all(|y| {
+            (x0..x0 + target.size.width as i32)
+                .all(|x| rects.iter().any(|r| r.contains(Point::new(x, y))))
+        })
+    }
+
+    fn assert_disjoint(rects: &[Rectangle]) {
+        for (index, a) in rects.iter().enumerate() {
+            for b in &rects[index + 1..] {
+                assert!(!overlaps(a, b), "{a:?} overlaps {b:?}");
+            }
+        }
+    }
+
+    /// 简单的线性同余序列，测试不依赖随机数库
+    struct Lcg(u32);
+
+    impl Lcg {
+        fn next(&mut self, bound: u32) -> u32 {
+            self.0 = self.0.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (self.0 >> 16) % bound
+        }
+    }
+
+    #[test]
+    fn add_keeps_rects_disjoint_and_covering() {
+        let mut random = Lcg(1);
+        for _ in 0..200 {
+            let mut dirty = DirtyRects::new();
+            let mut added: Vec<Rectangle, 12> = Vec::new();
+            for _ in 0..12 {
+                let r = rect(
+                    random.next(60) as i32,
+                    random.next(60) as i32,
+                    random.next(30) + 1,
+                    random.next(30) + 1,
+                );
+                dirty.add(r);
+                added.push(r).unwrap();
+
+                assert_disjoint(dirty.rects());
+                for r in &added {
+                    assert!(covers(dirty.rects(), r), "{r:?} not covered");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn contained_rect_is_ignored() {
+        let mut dirty = DirtyRects::new();
+        dirty.add(rect(0, 0, 20, 20));
+        dirty.add(rect(5, 5, 4, 4));
+        assert_eq!(dirty.rects(), &[rect(0, 0, 20, 20)]);
+
+        dirty.add(rect(-1, -1, 30, 30));
+        assert_eq!(dirty.rects(), &[rect(-1, -1, 30, 30)]);
+    }
+
+    #[test]
+    fn overflow_absorbs_into_existing_rects() {
+        let mut dirty = DirtyRects::new();
+        let added: [Rectangle; MAX_DIRTY_RECTS + 4] =
+            core::array::from_fn(|i| rect(i as i32 * 20, (i % 3) as i32 * 40, 4, 4));
+        for r in added {
+            dirty.add(r);
+        }
+
+        assert_eq!(dirty.rects().len(), MAX_DIRTY_RECTS);
+        assert_disjoint(dirty.rects());
+        for r in &added {
+            assert!(covers(dirty.rects(), r), "{r:?} not covered");
+        }
+    }
+
+    #[test]
+    fn absorb_merges_overlapping_rects() {
+        let mut dirty = DirtyRects::new();
+        dirty.rects.push(rect(0, 0, 10, 10)).unwrap();
+        dirty.rects.push(rect(50, 50, 10, 10)).unwrap();
+        dirty.absorb(rect(5, 5, 10, 10));
+
+        assert_eq!(dirty.rects().len(), 2);
+        assert!(dirty.rects().contains(&rect(0, 0, 15, 15)));
+        assert!(dirty.rects().contains(&rect(50, 50, 10, 10)));
+    }
+
+    #[test]
+    fn worth_merging_adjacent_but_not_distant() {
+        assert!(worth_merging(&rect(0, 0, 10, 10), &rect(10, 0, 10, 10)));
+        assert!(worth_merging(&rect(0, 0, 10, 10), &rect(2, 2, 10, 10)));
+        assert!(!worth_merging(&rect(0, 0, 10, 10), &rect(40, 40, 10, 10)));
+        assert!(!worth_merging(&rect(0, 0, 40, 2), &rect(0, 0, 2, 40)));
+    }
+
+    #[test]
+    fn subtract_leaves_disjoint_pieces_outside_hole() {
+        let whole = rect(0, 0, 20, 20);
+        for hole in [rect(5, 5, 5, 5), rect(-5, -5, 10, 10), rect(15, 0, 10, 30)] {
+            let pieces = subtract(&whole, &hole);
+            assert_disjoint(&pieces);
+            for piece in &pieces {
+                assert!(!overlaps(piece, &hole));
+                assert!(contains(&whole, piece));
+            }
+            let remaining: u32 = pieces.iter().map(area).sum();
+            assert_eq!(remaining, area(&whole) - area(&whole.intersection(&hole)));
+        }
+
+        let apart = rect(30, 30, 5, 5);
+        assert_eq!(subtract(&whole, &apart).as_slice(), &[whole]);
+    }
+
+    #[test]
+    fn union_ignores_empty_rects() {
+        let a = rect(3, 4, 5, 6);
+        assert_eq!(union(&a, &rect(0, 0, 0, 0)), a);
+        assert_eq!(union(&a, &rect(10, 10, 2, 2)), rect(3, 4, 9, 8));
+    }
+}
@@ -0,0 +1,380 @@
+use core::convert::Infallible;
+
+use embedded_graphics_core::{
+    Pixel,
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Point, Size},
+    pixelcolor::Rgb565,
+    primitives::Rectangle,
+};
+
+use super::dirty::DirtyRects;
+use super::framebuffer::Framebuffer;
+use crate::driver::panel::WindowWrite;
+
+/// 双缓冲：绘制到后缓冲，[`DoubleBuffered::swap`]时呈现并交换
+///
+/// 不依赖具体驱动，任何[`WindowWrite`]都可作为目标（ST7735请用直接模式）。
+/// 呈现前先调用[`WindowWrite::wait_frame`]，按驱动配置的TE/节流同步。
+///
+/// 只发送可能变化的区域：后缓冲自上次呈现以来的脏区，加上上一次呈现发送的区域。
+/// 开启[`DoubleBuffered::diff`]后再与前缓冲逐行比较，只发送确实改变的像素所在的矩形。
+///
+/// 两块帧缓冲各占`W * H * 2`字节，大屏可放在PSRAM中，见[`super::psram`]。
+pub struct DoubleBuffered<'a, D, const W: usize, const H: usize> {
+    display: D,
+    /// 屏幕上正显示的内容
+    front: &'a mut Framebuffer<W, H>,
+    /// 正在绘制的下一帧
+    back: &'a mut Framebuffer<W, H>,
+    /// 上一次呈现发送的区域，即屏幕与后缓冲原内容可能不同之处
+    presented: DirtyRects,
+    /// 屏幕内容未知或可能与前缓冲不一致，下次呈现不做比较
+    resync: bool,
+    diff: bool,
+    preserve: bool,
+}
+
+impl<'a, D: WindowWrite, const W: usize, const H: usize> DoubleBuffered<'a, D, W, H> {
+    /// 屏幕原有内容未知，第一次呈现会发送整屏
+    pub fn new(
+        display: D,
+        front: &'a mut Framebuffer<W, H>,
+        back: &'a mut Framebuffer<W, H>,
+    ) -> Self {
+        let mut this = Self {
+            display,
+            front,
+            back,
+            presented: DirtyRects::new(),
+            resync: false,
+            diff: false,
+            preserve: false,
+        };
+        this.invalidate();
+        this
+    }
+
+    /// 呈现前与前缓冲逐行比较，只发送改变的像素
+    ///
+    /// 比较只读内存，通常比多发送的像素便宜得多。
+    pub fn diff(mut self, enabled: bool) -> Self {
+        self.diff = enabled;
+        self
+    }
+
+    /// 交换后把刚呈现的内容同步到新的后缓冲，可在上一帧基础上增量绘制
+    ///
+    /// 关闭时交换后的后缓冲是再早一帧的内容，每帧都应完整重绘。
+    pub fn preserve(mut self, enabled: bool) -> Self {
+        self.preserve = enabled;
+        self
+    }
+
+    /// 正在绘制的后缓冲
+    pub fn back(&self) -> &Framebuffer<W, H> {
+        self.back
+    }
+
+    pub fn back_mut(&mut self) -> &mut Framebuffer<W, H> {
+        self.back
+    }
+
+    /// 屏幕上正显示的内容
+    pub fn front(&self) -> &Framebuffer<W, H> {
+        self.front
+    }
+
+    pub fn display(&self) -> &D {
+        &self.display
+    }
+
+    /// 直接操作显示驱动，改动了屏幕内容后应[`DoubleBuffered::invalidate`]
+    pub fn display_mut(&mut self) -> &mut D {
+        &mut self.display
+    }
+
+    /// 下次呈现时发送整屏
+    pub fn invalidate(&mut self) {
+        self.presented.clear();
+        self.presented.add(self.back.bounds());
+        self.resync = true;
+    }
+
+    /// 呈现后缓冲并交换
+    ///
+    /// 出错时不交换，下次呈现按整块重发涉及的区域。
+    pub fn swap(&mut self) -> Result<(), D::Error> {
+        let mut candidates = DirtyRects::new();
+        for rect in self.back.dirty_rects().iter().chain(self.presented.rects()) {
+            candidates.add(*rect);
+        }
+
+        let sent = if self.diff && !self.resync {
+            self.changed_rects(candidates.rects())
+        } else {
+            candidates
+        };
+
+        self.display.wait_frame()?;
+        for rect in sent.rects() {
+            if let Err(error) = self.display.write_rows(rect, self.back.area_rows(rect)) {
+                self.resync = true;
+                return Err(error);
+            }
+        }
+
+        self.back.clear_dirty();
+        self.front.clear_dirty();
+        core::mem::swap(&mut self.front, &mut self.back);
+        if self.preserve {
+            // 新的后缓冲已与屏幕一致，下次只需发送它自己的脏区
+            for rect in sent.rects() {
+                self.back.copy_area_from(self.front, rect);
+            }
+            self.presented.clear();
+        } else {
+            self.presented = sent;
+        }
+        self.resync = false;
+        Ok(())
+    }
+
+    /// 拆回显示驱动与两块帧缓冲（前、后）
+    pub fn release(self) -> (D, &'a mut Framebuffer<W, H>, &'a mut Framebuffer<W, H>) {
+        (self.display, self.front, self.back)
+    }
+
+    /// 在候选区域内逐行比较前后缓冲，连续改变的行合为一个矩形
+    fn changed_rects(&self, candidates: &[Rectangle]) -> DirtyRects {
+        let mut changed = DirtyRects::new();
+        for rect in candidates {
+            let x0 = rect.top_left.x as usize;
+            let y0 = rect.top_left.y as usize;
+            let width = rect.size.width as usize;
+
+            // 当前连续改变的行：(起始行, 最左列, 最右列)
+            let mut run: Option<(usize, usize, usize)> = None;
+            for y in y0..y0 + rect.size.height as usize {
+                let front = &self.front.row(y)[x0..x0 + width];
+                let back = &self.back.row(y)[x0..x0 + width];
+                let first = front.iter().zip(back).position(|(a, b)| a != b);
+
+                match (first, run) {
+                    (Some(first), _) => {
+                        let last = front
+                            .iter()
+                            .zip(back)
+                            .rposition(|(a, b)| a != b)
+                            .unwrap_or(first);
+                        run = Some(match run {
+                            Some((start, left, right)) => (start, left.min(first), right.max(last)),
+                            None => (y, first, last),
+                        });
+                    }
+                    (None, Some(span)) => {
+                        changed.add(span_rect(x0, span, y));
+                        run = None;
+                    }
+                    (None, None) => {}
+                }
+            }
+            if let Some(span) = run {
+                changed.add(span_rect(x0, span, y0 + rect.size.height as usize));
+            }
+        }
+        changed
+    }
+}
+
+/// 由连续改变的行`(起始行, 最左列, 最右列)`与结束行（不含）得到矩形
+fn span_rect(x0: usize, (start, left, right): (usize, usize, usize), end: usize) -> Rectangle {
+    Rectangle::new(
+        Point::new((x0 + left) as i32, start as i32),
+        Size::new((right - left + 1) as u32, (end - start) as u32),
+    )
+}
+
+impl<D, const W: usize, const H: usize> OriginDimensions for DoubleBuffered<'_, D, W, H> {
+    fn size(&self) -> Size {
+        Size::new(W as u32, H as u32)
+    }
+}
+
+/// 绘制到后缓冲
+impl<D, const W: usize, const H: usize> DrawTarget for DoubleBuffered<'_, D, W, H> {
+    type Color = Rgb565;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.back.draw_iter(pixels)
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.back.fill_contiguous(area, colors)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.back.fill_solid(area, color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.back.clear(color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::pixelcolor::RgbColor;
+
+    use super::*;
+
+    const W: usize = 16;
+    const H: usize = 12;
+
+    /// 记录写入内容的假屏幕
+    struct MockScreen {
+        screen: [[Rgb565; W]; H],
+        /// 累计发送的像素数
+        sent: usize,
+        /// 为真时下一次写入失败
+        fail: bool,
+    }
+
+    impl MockScreen {
+        fn new() -> Self {
+            Self {
+                screen: [[Rgb565::BLACK; W]; H],
+                sent: 0,
+                fail: false,
+            }
+        }
+    }
+
+    impl WindowWrite for MockScreen {
+        type Error = ();
+
+        fn write_window<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), ()>
+        where
+            I: IntoIterator<Item = Rgb565>,
+        {
+            if core::mem::take(&mut self.fail) {
+                return Err(());
+            }
+            let x0 = area.top_left.x as usize;
+            let y0 = area.top_left.y as usize;
+            let width = area.size.width as usize;
+            let count = width * area.size.height as usize;
+            for (index, color) in colors.into_iter().take(count).enumerate() {
+                self.screen[y0 + index / width][x0 + index % width] = color;
+                self.sent += 1;
+            }
+            Ok(())
+        }
+    }
+
+    /// 第`frame`帧：底色上一个移动的方块和一个变色的点
+    fn draw_frame<T: DrawTarget<Color = Rgb565>>(target: &mut T, frame: i32)
+    where
+        T::Error: core::fmt::Debug,
+    {
+        let block = Rectangle::new(Point::new(frame * 2 % 12, frame % 9), Size::new(3, 3));
+        let dot = Rectangle::new(Point::new(W as i32 - 1, H as i32 - 1), Size::new(1, 1));
+        let shade = Rgb565::new(frame as u8 % 32, 0, 31);
+        target.fill_solid(&block, Rgb565::RED).unwrap();
+        target.fill_solid(&dot, shade).unwrap();
+    }
+
+    fn assert_screen(screen: &MockScreen, expected: &Framebuffer<W, H>) {
+        for (y, row) in screen.screen.iter().enumerate() {
+            assert_eq!(&row[..], expected.row(y), "row {y}");
+        }
+    }
+
+    #[test]
+    fn screen_matches_last_frame_after_swaps() {
+        for (diff, preserve) in [(false, false), (false, true), (true, false), (true, true)] {
+            let mut front = Framebuffer::<W, H>::new();
+            let mut back = Framebuffer::<W, H>::new();
+            let mut buffered = DoubleBuffered::new(MockScreen::new(), &mut front, &mut back)
+                .diff(diff)
+                .preserve(preserve);
+
+            for frame in 0..8 {
+                let mut expected = Framebuffer::<W, H>::new();
+                expected.clear(Rgb565::BLUE).unwrap();
+                draw_frame(&mut expected, frame);
+
+                if preserve && frame > 0 {
+                    // 增量绘制：擦掉上一帧的方块再画新的
+                    let block = Rectangle::new(
+                        Point::new((frame - 1) * 2 % 12, (frame - 1) % 9),
+                        Size::new(3, 3),
+                    );
+                    buffered.fill_solid(&block, Rgb565::BLUE).unwrap();
+                } else {
+                    buffered.clear(Rgb565::BLUE).unwrap();
+                }
+                draw_frame(&mut buffered, frame);
+
+                let before = buffered.display().sent;
+                buffered.swap().unwrap();
+                let sent = buffered.display().sent - before;
+                assert_screen(buffered.display(), &expected);
+                assert_eq!(buffered.front().as_slice(), expected.as_slice());
+                if diff && frame > 0 {
+                    // 只发送方块新旧位置附近与变色的点，远少于整屏
+                    assert!(sent < W * H / 2, "sent {sent} pixels");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn failed_write_is_resent_on_next_swap() {
+        for diff in [false, true] {
+            let mut front = Framebuffer::<W, H>::new();
+            let mut back = Framebuffer::<W, H>::new();
+            let mut buffered = DoubleBuffered::new(MockScreen::new(), &mut front, &mut back)
+                .diff(diff)
+                .preserve(true);
+            buffered.clear(Rgb565::BLUE).unwrap();
+            buffered.swap().unwrap();
+
+            let mut expected = Framebuffer::<W, H>::new();
+            expected.clear(Rgb565::BLUE).unwrap();
+            draw_frame(&mut expected, 3);
+            draw_frame(&mut buffered, 3);
+
+            buffered.display_mut().fail = true;
+            assert_eq!(buffered.swap(), Err(()));
+            assert!(
+                buffered
+                    .display()
+                    .screen
+                    .iter()
+                    .flatten()
+                    .all(|&c| c == Rgb565::BLUE)
+            );
+
+            buffered.swap().unwrap();
+            assert_screen(buffered.display(), &expected);
+
+            // 之后的增量绘制照常
+            let mut next = Framebuffer::<W, H>::new();
+            next.clear(Rgb565::BLUE).unwrap();
+            draw_frame(&mut next, 3);
+            let dot = Rectangle::new(Point::new(0, H as i32 - 1), Size::new(2, 1));
+            next.fill_solid(&dot, Rgb565::GREEN).unwrap();
+            buffered.fill_solid(&dot, Rgb565::GREEN).unwrap();
+            buffered.swap().unwrap();
+            assert_screen(buffered.display(), &next);
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use core::convert::Infallible;
+use core::mem::MaybeUninit;
+use core::ptr::addr_of_mut;
+
+use embedded_graphics_core::{
+    Pixel,
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Point, Size},
+    pixelcolor::{Rgb565, RgbColor},
+    primitives::{PointsIter, Rectangle},
+};
+
+use super::dirty::{DirtyRects, union};
+use super::psram::InitInPlace;
+use crate::driver::panel::WindowWrite;
+
+/// 内存中的RGB565帧缓冲，记录改动过的区域
+///
+/// 绘制只改内存；[`Framebuffer::flush`]只把脏矩形按窗口发给显示驱动。
+/// 像素值未变的绘制不会产生脏区。
+///
+/// 占用`W * H * 2`字节，应放在`static`中而不是栈上；片上内存放不下时
+/// 可放到PSRAM，见[`super::psram`]。
+#[derive(Clone)]
+pub struct Framebuffer<const W: usize, const H: usize> {
+    pixels: [[Rgb565; W]; H],
+    dirty: DirtyRects,
+}
+
+impl<const W: usize, const H: usize> Framebuffer<W, H> {
+    /// 全黑、无脏区；屏幕上原有内容未知时先`clear`或[`Framebuffer::invalidate`]
+    pub const fn new() -> Self {
+        Self {
+            pixels: [[Rgb565::BLACK; W]; H],
+            dirty: DirtyRects::new(),
+        }
+    }
+
+    pub const fn width(&self) -> usize {
+        W
+    }
+
+    pub const fn height(&self) -> usize {
+        H
+    }
+
+    /// 整个缓冲区的范围
+    pub fn bounds(&self) -> Rectangle {
+        Rectangle::new(Point::zero(), Size::new(W as u32, H as u32))
+    }
+
+    /// 读取像素，越界时为`None`
+    pub fn pixel(&self, x: usize, y: usize) -> Option<Rgb565> {
+        self.pixels.get(y)?.get(x).copied()
+    }
+
+    /// 第`y`行
+    pub fn row(&self, y: usize) -> &[Rgb565] {
+        &self.pixels[y]
+    }
+
+    /// 按行优先的全部像素
+    pub fn as_slice(&self) -> &[Rgb565] {
+        self.pixels.as_flattened()
+    }
+
+    /// 可直接修改的全部像素，改动后需自行[`Framebuffer::mark_dirty`]
+    pub fn as_mut_slice(&mut self) -> &mut [Rgb565] {
+        self.pixels.as_flattened_mut()
+    }
+
+    /// 当前的脏矩形
+    pub fn dirty_rects(&self) -> &[Rectangle] {
+        self.dirty.rects()
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        !self.dirty.is_empty()
+    }
+
+    /// 标记区域需要重发
+    pub fn mark_dirty(&mut self, area: &Rectangle) {
+        self.dirty.add(area.intersection(&self.bounds()));
+    }
+
+    /// 清空脏矩形，屏幕已与缓冲区一致时使用
+    pub fn clear_dirty(&mut self) {
+        self.dirty.clear();
+    }
+
+    /// 下次刷新时重发整屏
+    pub fn invalidate(&mut self) {
+        self.dirty.clear();
+        self.dirty.add(self.bounds());
+    }
+
+    /// `area`（须已在范围内）中的像素，按行优先
+    pub fn area_pixels(&self, area: &Rectangle) -> impl Iterator<Item = Rgb565> + '_ {
+        let x0 = area.top_left.x as usize;
+        let y0 = area.top_left.y as usize;
+        let width = area.size.width as usize;
+        self.pixels[y0..y0 + area.size.height as usize]
+            .iter()
+            .flat_map(move |row| row[x0..x0 + width].iter().copied())
+    }
+
+    /// `area`（须已在范围内）覆盖的各行片段
+    pub fn area_rows(&self, area: &Rectangle) -> impl Iterator<Item = &[Rgb565]> + '_ {
+        let x0 = area.top_left.x as usize;
+        let y0 = area.top_left.y as usize;
+        let width = area.size.width as usize;
+        self.pixels[y0..y0 + area.size.height as usize]
+            .iter()
+            .map(move |row| &row[x0..x0 + width])
+    }
+
+    /// 不论脏区，把`area`整块发送到显示驱动（按行批量拷贝），不改变脏矩形
+    pub fn write_to<D: WindowWrite>(
+        &self,
+        display: &mut D,
+        area: &Rectangle,
+    ) -> Result<(), D::Error> {
+        let area = area.intersection(&self.bounds());
+        if area.size.width == 0 || area.size.height == 0 {
+            return Ok(());
+        }
+        display.write_rows(&area, self.area_rows(&area))
+    }
+
+    /// 把脏矩形发送到显示驱动并清空
+    ///
+    /// 中途出错时未发送的区域保留，下次刷新会重试。
+    pub fn flush<D: WindowWrite>(&mut self, display: &mut D) -> Result<(), D::Error> {
+        let rects = self.dirty.take();
+        for (index, rect) in rects.iter().enumerate() {
+            if let Err(error) = display.write_rows(rect, self.area_rows(rect)) {
+                for rect in &rects[index..] {
+                    self.dirty.add(*rect);
+                }
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    /// 从`other`拷贝`area`（须已在范围内），不记脏区
+    pub(crate) fn copy_area_from(&mut self, other: &Self, area: &Rectangle) {
+        let x0 = area.top_left.x as usize;
+        let y0 = area.top_left.y as usize;
+        let width = area.size.width as usize;
+        for y in y0..y0 + area.size.height as usize {
+            self.pixels[y][x0..x0 + width].copy_from_slice(&other.pixels[y][x0..x0 + width]);
+        }
+    }
+
+    /// 写入像素，返回值是否改变
+    fn set(&mut self, point: Point, color: Rgb565) -> bool {
+        let pixel = &mut self.pixels[point.y as usize][point.x as usize];
+        let changed = *pixel != color;
+        *pixel = color;
+        changed
+    }
+}
+
+/// 像素清零（黑色）、无脏区，与[`Framebuffer::new`]相同
+unsafe impl<const W: usize, const H: usize> InitInPlace for Framebuffer<W, H> {
+    fn init_in_place(slot: &mut MaybeUninit<Self>) {
+        let this = slot.as_mut_ptr();
+        // SAFETY: 只写入字段，不读取未初始化的内存；像素为`W * H`个连续的Rgb565
+        unsafe {
+            let pixels = addr_of_mut!((*this).pixels) as *mut Rgb565;
+            for index in 0..W * H {
+                pixels.add(index).write(Rgb565::BLACK);
+            }
+            addr_of_mut!((*this).dirty).write(DirtyRects::new());
+        }
+    }
+}
+
+impl<const W: usize, const H: usize> Default for Framebuffer<W, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize, const H: usize> OriginDimensions for Framebuffer<W, H> {
+    fn size(&self) -> Size {
+        Size::new(W as u32, H as u32)
+    }
+}
+
+impl<const W: usize, const H: usize> DrawTarget for Framebuffer<W, H> {
+    type Color = Rgb565;
+    type Error = Infallible;
+
+    /// 一次绘制中改变的像素合为一个外包矩形记入脏区
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let bounds = self.bounds();
+        let mut changed = Rectangle::zero();
+        for Pixel(point, color) in pixels {
+            if bounds.contains(point) && self.set(point, color) {
+                changed = union(&changed, &Rectangle::new(point, Size::new(1, 1)));
+            }
+        }
+        self.dirty.add(changed);
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let bounds = self.bounds();
+        let mut changed = Rectangle::zero();
+        for (point, color) in area.points().zip(colors) {
+            if bounds.contains(point) && self.set(point, color) {
+                changed = union(&changed, &Rectangle::new(point, Size::new(1, 1)));
+            }
+        }
+        self.dirty.add(changed);
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.bounds());
+        if area.size.width == 0 || area.size.height == 0 {
+            return Ok(());
+        }
+
+        let x0 = area.top_left.x as usize;
+        let y0 = area.top_left.y as usize;
+        let width = area.size.width as usize;
+        let mut changed = false;
+        for row in &mut self.pixels[y0..y0 + area.size.height as usize] {
+            let row = &mut row[x0..x0 + width];
+            changed |= row.iter().any(|&pixel| pixel != color);
+            row.fill(color);
+        }
+        if changed {
+            self.dirty.add(area);
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.fill_solid(&self.bounds(), color)
+    }
+}
@@ -0,0 +1,17 @@
+pub mod band;
+pub mod compositor;
+pub mod dirty;
+pub mod double;
+pub mod framebuffer;
+pub mod psram;
+pub mod sprite;
+pub mod viewport;
+
+pub use band::{Band, BandDraw, BandRenderer, DrawList};
+pub use compositor::{Compositor, Layer, LayerId, LayerSource};
+pub use dirty::{DirtyRects, MAX_DIRTY_RECTS};
+pub use double::DoubleBuffered;
+pub use framebuffer::Framebuffer;
+pub use psram::{InitInPlace, PsramArena, PsramCell};
+pub use sprite::{Sprite, SpriteEngine, SpriteFrame, SpriteId, SpriteSheet, Transparency};
+pub use viewport::Viewport;
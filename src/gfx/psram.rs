@@ -0,0 +1,216 @@
+use core::cell::UnsafeCell;
+use core::mem::{MaybeUninit, align_of, size_of};
+#[cfg(feature = "psram")]
+use core::ops::Range;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// PSRAM区域的地址范围，取自链接脚本导出的`_psram_base`/`_psram_end`
+#[cfg(feature = "psram")]
+pub fn psram_range() -> Range<usize> {
+    unsafe extern "C" {
+        static _psram_base: u8;
+        static _psram_end: u8;
+    }
+
+    &raw const _psram_base as usize..&raw const _psram_end as usize
+}
+
+/// 地址是否落在PSRAM中
+#[cfg(feature = "psram")]
+pub fn is_psram(ptr: *const u8) -> bool {
+    psram_range().contains(&(ptr as usize))
+}
+
+/// 可在原地初始化的类型，大对象不必先在栈上构造再拷贝
+///
+/// # Safety
+///
+/// `init_in_place`返回后`slot`必须已完整初始化。
+pub unsafe trait InitInPlace: Sized {
+    fn init_in_place(slot: &mut MaybeUninit<Self>);
+}
+
+/// 每个元素取默认值
+unsafe impl<T: Default, const N: usize> InitInPlace for [T; N] {
+    fn init_in_place(slot: &mut MaybeUninit<Self>) {
+        let items = slot.as_mut_ptr() as *mut T;
+        for index in 0..N {
+            // SAFETY: 数组内N个连续元素，逐个写入
+            unsafe { items.add(index).write(T::default()) };
+        }
+    }
+}
+
+/// `.psram`段中的存储，由[`psram_static!`](crate::psram_static)生成，不直接使用
+#[doc(hidden)]
+pub struct PsramSlot<T>(UnsafeCell<MaybeUninit<T>>);
+
+// SAFETY: 只能经PsramCell取出一次
+unsafe impl<T: Send> Sync for PsramSlot<T> {}
+
+impl<T> PsramSlot<T> {
+    pub const fn uninit() -> Self {
+        Self(UnsafeCell::new(MaybeUninit::uninit()))
+    }
+}
+
+/// PSRAM中的静态对象，只能取出一次，用[`psram_static!`](crate::psram_static)定义
+///
+/// 存储在`.psram`段中（需启用`psram`特性）。该段为NOLOAD，启动时不清零，
+/// 所以取出时才初始化；取出标记在普通内存中，上电后为未取出。
+pub struct PsramCell<T: 'static> {
+    slot: &'static PsramSlot<T>,
+    taken: AtomicBool,
+}
+
+impl<T> PsramCell<T> {
+    #[doc(hidden)]
+    pub const fn new(slot: &'static PsramSlot<T>) -> Self {
+        Self {
+            slot,
+            taken: AtomicBool::new(false),
+        }
+    }
+
+    /// 原地初始化后取出，再次调用返回`None`
+    pub fn take(&self) -> Option<&'static mut T>
+    where
+        T: InitInPlace,
+    {
+        let slot = self.claim()?;
+        T::init_in_place(slot);
+        // SAFETY: init_in_place保证已初始化
+        Some(unsafe { slot.assume_init_mut() })
+    }
+
+    /// 以`value`初始化后取出，适合小对象
+    pub fn take_with(&self, value: T) -> Option<&'static mut T> {
+        Some(self.claim()?.write(value))
+    }
+
+    fn claim(&self) -> Option<&'static mut MaybeUninit<T>> {
+        if self.taken.swap(true, Ordering::AcqRel) {
+            return None;
+        }
+        // SAFETY: 标记保证只有这一个可变引用
+        Some(unsafe { &mut *self.slot.0.get() })
+    }
+}
+
+/// 在PSRAM中定义静态对象
+///
+/// ```ignore
+/// psram_static! {
+///     static FRAME: Framebuffer<240, 240>;
+/// }
+///
+/// let fb = FRAME.take().unwrap();
+/// ```
+#[macro_export]
+macro_rules! psram_static {
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $ty:ty;) => {
+        $(#[$attr])*
+        $vis static $name: $crate::gfx::psram::PsramCell<$ty> = {
+            #[unsafe(link_section = ".psram")]
+            static SLOT: $crate::gfx::psram::PsramSlot<$ty> = $crate::gfx::psram::PsramSlot::uninit();
+            $crate::gfx::psram::PsramCell::new(&SLOT)
+        };
+    };
+}
+
+/// PSRAM区域上的分配器：只增不减，分出的对象一直有效
+///
+/// 适合启动时按屏幕尺寸分配帧缓冲，或把Flash中的图片拷进来做缓存。
+/// 启用`psram`特性后链接脚本会预留一块区域，大小由环境变量
+/// `ECOS_PSRAM_ARENA_SIZE`指定（字节，默认256KB）。
+pub struct PsramArena {
+    next: usize,
+    end: usize,
+}
+
+impl PsramArena {
+    /// 在`[start, end)`上创建
+    ///
+    /// # Safety
+    ///
+    /// 该区域可读写、不被其他代码（包括全局分配器）使用，且只创建一个分配器。
+    pub const unsafe fn from_range(start: usize, end: usize) -> Self {
+        Self { next: start, end }
+    }
+
+    /// 链接脚本预留的区域，只能取一次
+    #[cfg(feature = "psram")]
+    pub fn take() -> Option<Self> {
+        static TAKEN: AtomicBool = AtomicBool::new(false);
+
+        unsafe extern "C" {
+            static _psram_arena_start: u8;
+            static _psram_arena_end: u8;
+        }
+
+        if TAKEN.swap(true, Ordering::AcqRel) {
+            return None;
+        }
+        // SAFETY: 区域由链接脚本专门预留，标记保证只创建一次
+        unsafe {
+            let start = &raw const _psram_arena_start as usize;
+            let end = &raw const _psram_arena_end as usize;
+            Some(Self::from_range(start, end))
+        }
+    }
+
+    /// 剩余字节数（未计对齐）
+    pub fn remaining(&self) -> usize {
+        self.end - self.next
+    }
+
+    /// 分配并原地初始化，空间不足时为`None`
+    pub fn alloc<T: InitInPlace>(&mut self) -> Option<&'static mut T> {
+        let slot = self.alloc_uninit::<T>()?;
+        T::init_in_place(slot);
+        // SAFETY: init_in_place保证已初始化
+        Some(unsafe { slot.assume_init_mut() })
+    }
+
+    /// 分配并以`value`初始化
+    pub fn alloc_with<T>(&mut self, value: T) -> Option<&'static mut T> {
+        Some(self.alloc_uninit::<T>()?.write(value))
+    }
+
+    /// 分配`len`个元素并全部填为`fill`
+    pub fn alloc_slice<T: Copy>(&mut self, len: usize, fill: T) -> Option<&'static mut [T]> {
+        let items = self.alloc_array::<T>(len)?;
+        for index in 0..len {
+            // SAFETY: alloc_array分出了len个元素的空间
+            unsafe { items.add(index).write(fill) };
+        }
+        // SAFETY: 已全部写入
+        Some(unsafe { core::slice::from_raw_parts_mut(items, len) })
+    }
+
+    /// 把`data`（如Flash中的图片）拷贝一份到PSRAM
+    pub fn alloc_copy<T: Copy>(&mut self, data: &[T]) -> Option<&'static mut [T]> {
+        let items = self.alloc_array::<T>(data.len())?;
+        // SAFETY: 新分配的空间与data不重叠
+        unsafe {
+            core::ptr::copy_nonoverlapping(data.as_ptr(), items, data.len());
+            Some(core::slice::from_raw_parts_mut(items, data.len()))
+        }
+    }
+
+    fn alloc_uninit<T>(&mut self) -> Option<&'static mut MaybeUninit<T>> {
+        let ptr = self.alloc_array::<T>(1)?;
+        // SAFETY: 新分配、对齐且不与其他分配重叠
+        Some(unsafe { &mut *(ptr as *mut MaybeUninit<T>) })
+    }
+
+    fn alloc_array<T>(&mut self, len: usize) -> Option<*mut T> {
+        let start = self.next.checked_next_multiple_of(align_of::<T>())?;
+        let end = start.checked_add(size_of::<T>().checked_mul(len)?)?;
+        if end > self.end {
+            return None;
+        }
+        self.next = end;
+        Some(start as *mut T)
+    }
+}
@@ -0,0 +1,390 @@
+use embedded_graphics_core::{
+    Drawable, Pixel,
+    draw_target::DrawTarget,
+    geometry::{Dimensions, Point, Size},
+    pixelcolor::{Rgb565, RgbColor, raw::RawU16},
+    primitives::{PointsIter, Rectangle},
+};
+use heapless::Vec;
+
+use super::band::Band;
+use super::compositor::LayerSource;
+use super::dirty::DirtyRects;
+
+/// 精灵表的透明方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transparency<'a> {
+    /// 全部不透明
+    None,
+    /// 等于此颜色的像素透明
+    ColorKey(Rgb565),
+    /// 1位遮罩，覆盖整张表：行优先、每行补齐到整字节、高位在前，1为不透明
+    Mask(&'a [u8]),
+}
+
+/// 精灵表：大端RGB565像素（可直接`include_bytes!`），切成等大的帧
+///
+/// 帧从左到右、从上到下编号，表宽高不是帧的整数倍时多出的部分不用。
+#[derive(Debug, Clone, Copy)]
+pub struct SpriteSheet<'a> {
+    data: &'a [u8],
+    width: u32,
+    height: u32,
+    frame_size: Size,
+    transparency: Transparency<'a>,
+}
+
+impl<'a> SpriteSheet<'a> {
+    /// `data`为`width`像素宽的大端RGB565，每帧`frame_size`
+    pub const fn new(data: &'a [u8], width: u32, frame_size: Size) -> Self {
+        Self {
+            data,
+            width,
+            height: match ((data.len() / 2) as u32).checked_div(width) {
+                Some(height) => height,
+                None => 0,
+            },
+            frame_size,
+            transparency: Transparency::None,
+        }
+    }
+
+    pub const fn color_key(mut self, key: Rgb565) -> Self {
+        self.transparency = Transparency::ColorKey(key);
+        self
+    }
+
+    /// 1位遮罩，长度不足的部分按透明处理
+    pub const fn mask(mut self, mask: &'a [u8]) -> Self {
+        self.transparency = Transparency::Mask(mask);
+        self
+    }
+
+    pub const fn frame_size(&self) -> Size {
+        self.frame_size
+    }
+
+    /// 每行的帧数
+    pub const fn columns(&self) -> u32 {
+        match self.width.checked_div(self.frame_size.width) {
+            Some(columns) => columns,
+            None => 0,
+        }
+    }
+
+    pub const fn frame_count(&self) -> usize {
+        match self.height.checked_div(self.frame_size.height) {
+            Some(rows) => (self.columns() * rows) as usize,
+            None => 0,
+        }
+    }
+
+    /// 第`index`帧在表中的位置
+    pub fn frame_rect(&self, index: usize) -> Option<Rectangle> {
+        if index >= self.frame_count() {
+            return None;
+        }
+        let columns = self.columns() as usize;
+        let top_left = Point::new(
+            ((index % columns) as u32 * self.frame_size.width) as i32,
+            ((index / columns) as u32 * self.frame_size.height) as i32,
+        );
+        Some(Rectangle::new(top_left, self.frame_size))
+    }
+
+    /// 第`index`帧放在`position`处，可直接绘制
+    pub fn frame(&self, index: usize, position: Point) -> SpriteFrame<'_, 'a> {
+        SpriteFrame {
+            sheet: self,
+            index,
+            position,
+        }
+    }
+
+    /// 表坐标处的像素，透明时为`None`
+    fn pixel(&self, x: u32, y: u32) -> Option<Rgb565> {
+        let index = (y * self.width + x) as usize;
+        if let Transparency::Mask(mask) = self.transparency {
+            let stride = self.width.div_ceil(8) as usize;
+            let byte = mask.get(y as usize * stride + x as usize / 8)?;
+            if byte & (0x80 >> (x % 8)) == 0 {
+                return None;
+            }
+        }
+
+        let bytes = self.data.get(index * 2..index * 2 + 2)?;
+        let color = Rgb565::from(RawU16::new(u16::from_be_bytes([bytes[0], bytes[1]])));
+        match self.transparency {
+            Transparency::ColorKey(key) if color == key => None,
+            _ => Some(color),
+        }
+    }
+}
+
+/// 精灵表中的一帧及其屏幕位置，透明像素不绘制
+#[derive(Debug, Clone, Copy)]
+pub struct SpriteFrame<'s, 'a> {
+    sheet: &'s SpriteSheet<'a>,
+    index: usize,
+    position: Point,
+}
+
+impl Dimensions for SpriteFrame<'_, '_> {
+    fn bounding_box(&self) -> Rectangle {
+        Rectangle::new(self.position, self.sheet.frame_size)
+    }
+}
+
+impl Drawable for SpriteFrame<'_, '_> {
+    type Color = Rgb565;
+    type Output = ();
+
+    /// 只遍历与目标相交的部分
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let Some(frame) = self.sheet.frame_rect(self.index) else {
+            return Ok(());
+        };
+        let visible = self.bounding_box().intersection(&target.bounding_box());
+        let offset = frame.top_left - self.position;
+
+        let pixels = visible.points().filter_map(|point| {
+            let source = point + offset;
+            let color = self.sheet.pixel(source.x as u32, source.y as u32)?;
+            Some(Pixel(point, color))
+        });
+        target.draw_iter(pixels)
+    }
+}
+
+/// 一个精灵：使用的表、当前帧与位置
+#[derive(Debug, Clone, Copy)]
+pub struct Sprite<'a> {
+    pub sheet: &'a SpriteSheet<'a>,
+    pub frame: usize,
+    pub position: Point,
+    pub visible: bool,
+}
+
+impl<'a> Sprite<'a> {
+    pub const fn new(sheet: &'a SpriteSheet<'a>, position: Point) -> Self {
+        Self {
+            sheet,
+            frame: 0,
+            position,
+            visible: true,
+        }
+    }
+
+    pub const fn frame(mut self, frame: usize) -> Self {
+        self.frame = frame;
+        self
+    }
+
+    pub const fn hidden(mut self) -> Self {
+        self.visible = false;
+        self
+    }
+
+    /// 屏幕上占据的范围
+    pub fn area(&self) -> Rectangle {
+        Rectangle::new(self.position, self.sheet.frame_size)
+    }
+
+    fn changed(&self, other: &Self) -> bool {
+        self.frame != other.frame
+            || self.position != other.position
+            || self.visible != other.visible
+            || !core::ptr::eq(self.sheet, other.sheet)
+    }
+}
+
+/// 精灵编号
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SpriteId(u8);
+
+impl SpriteId {
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// 精灵引擎：最多`N`个精灵，按加入顺序叠放（后加入的在上）
+///
+/// 精灵移动、换帧或显隐时，新旧两处范围记为脏区；[`SpriteEngine::render`]
+/// 只重绘脏区：按`W`宽、`LINES`行的条带先画背景，再画与之相交的精灵，
+/// 然后整块写入目标，屏幕上不会出现擦除后再画的闪烁。
+pub struct SpriteEngine<'a, const N: usize, const W: usize, const LINES: usize> {
+    sprites: Vec<Sprite<'a>, N>,
+    dirty: DirtyRects,
+    /// 下次重绘整个目标
+    full: bool,
+    buffer: [[Rgb565; W]; LINES],
+}
+
+impl<'a, const N: usize, const W: usize, const LINES: usize> SpriteEngine<'a, N, W, LINES> {
+    /// 第一次渲染时重绘整个目标
+    pub const fn new() -> Self {
+        const { assert!(N <= 256 && W > 0 && LINES > 0) };
+        Self {
+            sprites: Vec::new(),
+            dirty: DirtyRects::new(),
+            full: true,
+            buffer: [[Rgb565::BLACK; W]; LINES],
+        }
+    }
+
+    /// 加入一个精灵，已满时原样返回
+    pub fn add(&mut self, sprite: Sprite<'a>) -> Result<SpriteId, Sprite<'a>> {
+        let id = SpriteId(self.sprites.len() as u8);
+        self.sprites.push(sprite)?;
+        self.mark_sprite(&sprite);
+        Ok(id)
+    }
+
+    pub fn sprite(&self, id: SpriteId) -> &Sprite<'a> {
+        &self.sprites[id.index()]
+    }
+
+    pub fn len(&self) -> usize {
+        self.sprites.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sprites.is_empty()
+    }
+
+    /// 修改精灵，有变化时新旧范围都记为脏区
+    pub fn update(&mut self, id: SpriteId, f: impl FnOnce(&mut Sprite<'a>)) {
+        let old = self.sprites[id.index()];
+        let sprite = &mut self.sprites[id.index()];
+        f(sprite);
+        let new = *sprite;
+        if new.changed(&old) {
+            self.mark_sprite(&old);
+            self.mark_sprite(&new);
+        }
+    }
+
+    pub fn set_position(&mut self, id: SpriteId, position: Point) {
+        self.update(id, |sprite| sprite.position = position);
+    }
+
+    pub fn move_by(&mut self, id: SpriteId, delta: Point) {
+        self.update(id, |sprite| sprite.position += delta);
+    }
+
+    pub fn set_frame(&mut self, id: SpriteId, frame: usize) {
+        self.update(id, |sprite| sprite.frame = frame);
+    }
+
+    /// 切到下一帧，到末尾后回到第0帧
+    pub fn next_frame(&mut self, id: SpriteId) {
+        self.update(id, |sprite| {
+            let count = sprite.sheet.frame_count().max(1);
+            sprite.frame = (sprite.frame + 1) % count;
+        });
+    }
+
+    pub fn set_visible(&mut self, id: SpriteId, visible: bool) {
+        self.update(id, |sprite| sprite.visible = visible);
+    }
+
+    /// 背景在该区域改变，下次渲染时重绘
+    pub fn mark_dirty(&mut self, area: &Rectangle) {
+        self.dirty.add(*area);
+    }
+
+    /// 下次渲染时重绘整个目标
+    pub fn invalidate(&mut self) {
+        self.dirty.clear();
+        self.full = true;
+    }
+
+    /// 重绘脏区，`background`按屏幕坐标提供背景（帧缓冲或闭包），未覆盖处为黑色
+    ///
+    /// 出错时未写入的区域保留，下次渲染会重试。
+    pub fn render<D>(
+        &mut self,
+        target: &mut D,
+        background: &mut dyn LayerSource,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let bounds = target.bounding_box();
+        if core::mem::take(&mut self.full) {
+            self.dirty.clear();
+            self.dirty.add(bounds);
+        }
+
+        let rects = self.dirty.take();
+        for (index, rect) in rects.iter().enumerate() {
+            let area = rect.intersection(&bounds);
+            if let Err(error) = self.render_area(target, background, &area) {
+                for rect in &rects[index..] {
+                    self.dirty.add(*rect);
+                }
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    /// 按条带重绘一个矩形
+    fn render_area<D>(
+        &mut self,
+        target: &mut D,
+        background: &mut dyn LayerSource,
+        area: &Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let mut y = 0;
+        while y < area.size.height {
+            let lines = (area.size.height - y).min(LINES as u32);
+            let mut x = 0;
+            while x < area.size.width {
+                let width = (area.size.width - x).min(W as u32);
+                let band_area = Rectangle::new(
+                    area.top_left + Point::new(x as i32, y as i32),
+                    Size::new(width, lines),
+                );
+
+                // 先铺黑色，背景没有覆盖到的像素不会残留上一条带的内容
+                let len = (width * lines) as usize;
+                let pixels = &mut self.buffer.as_flattened_mut()[..len];
+                pixels.fill(Rgb565::BLACK);
+                let mut band = Band::new(pixels, band_area);
+                background.render(&mut band);
+                for sprite in self.sprites.iter().filter(|sprite| sprite.visible) {
+                    let _ = sprite
+                        .sheet
+                        .frame(sprite.frame, sprite.position)
+                        .draw(&mut band);
+                }
+
+                let pixels = &self.buffer.as_flattened()[..len];
+                target.fill_contiguous(&band_area, pixels.iter().copied())?;
+                x += width;
+            }
+            y += lines;
+        }
+        Ok(())
+    }
+
+    fn mark_sprite(&mut self, sprite: &Sprite<'a>) {
+        if sprite.visible {
+            self.dirty.add(sprite.area());
+        }
+    }
+}
+
+impl<const N: usize, const W: usize, const LINES: usize> Default for SpriteEngine<'_, N, W, LINES> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
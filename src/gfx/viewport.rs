@@ -0,0 +1,266 @@
+use embedded_graphics_core::{
+    Pixel,
+    draw_target::DrawTarget,
+    geometry::{Dimensions, Point},
+    primitives::{PointsIter, Rectangle},
+};
+
+/// 屏幕上的一块区域，有自己的原点与裁剪范围
+///
+/// 坐标`(0, 0)`对应区域左上角，区域外的绘制被裁掉；可按整数倍放大，
+/// 每个逻辑像素画成`scale × scale`的方块。视口本身也是[`DrawTarget`]，
+/// 可以再用[`Viewport::viewport`]划分子区域。
+///
+/// 适用于任何`DrawTarget`：显示驱动、[`super::Framebuffer`]、[`super::DoubleBuffered`]等。
+pub struct Viewport<'a, T> {
+    target: &'a mut T,
+    /// 目标坐标下的范围，已裁剪到目标内
+    area: Rectangle,
+    scale: u32,
+}
+
+impl<'a, T: DrawTarget> Viewport<'a, T> {
+    /// `area`为目标坐标，超出目标的部分被裁掉
+    pub fn new(target: &'a mut T, area: Rectangle) -> Self {
+        let area = area.intersection(&target.bounding_box());
+        Self {
+            target,
+            area,
+            scale: 1,
+        }
+    }
+
+    /// 按整数倍放大，逻辑尺寸为区域尺寸除以`scale`（向下取整）
+    pub fn scaled(mut self, scale: u32) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// 目标坐标下的范围
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// 划分子视口，`area`为本视口的逻辑坐标
+    pub fn viewport(&mut self, area: Rectangle) -> Viewport<'_, Self> {
+        Viewport::new(self, area)
+    }
+
+    /// 逻辑坐标转为目标坐标（放大时为方块左上角）
+    pub fn to_target(&self, point: Point) -> Point {
+        self.area.top_left + point * self.scale as i32
+    }
+
+    /// 目标坐标转为逻辑坐标，不在视口内时为`None`
+    pub fn from_target(&self, point: Point) -> Option<Point> {
+        if !self.area.contains(point) {
+            return None;
+        }
+        let point = (point - self.area.top_left) / self.scale as i32;
+        self.bounding_box().contains(point).then_some(point)
+    }
+}
+
+impl<T> Dimensions for Viewport<'_, T> {
+    fn bounding_box(&self) -> Rectangle {
+        Rectangle::new(Point::zero(), self.area.size / self.scale)
+    }
+}
+
+impl<T: DrawTarget> DrawTarget for Viewport<'_, T> {
+    type Color = T::Color;
+    type Error = T::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let bounds = self.bounding_box();
+        let origin = self.area.top_left;
+        let scale = self.scale as i32;
+        let pixels = pixels
+            .into_iter()
+            .filter(move |Pixel(point, _)| bounds.contains(*point));
+
+        if scale == 1 {
+            return self
+                .target
+                .draw_iter(pixels.map(move |Pixel(point, color)| Pixel(origin + point, color)));
+        }
+
+        // 方块按行展开，驱动可把同一行上连续的点合并发送
+        self.target
+            .draw_iter(pixels.flat_map(move |Pixel(point, color)| {
+                let top_left = origin + point * scale;
+                (0..scale * scale).map(move |index| {
+                    Pixel(top_left + Point::new(index % scale, index / scale), color)
+                })
+            }))
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        // 不放大且完全在视口内时整块交给目标，保留驱动的成批发送
+        if self.scale == 1 && area.intersection(&self.bounding_box()) == *area {
+            let area = Rectangle::new(self.area.top_left + area.top_left, area.size);
+            return self.target.fill_contiguous(&area, colors);
+        }
+
+        let pixels = area
+            .points()
+            .zip(colors)
+            .map(|(point, color)| Pixel(point, color));
+        self.draw_iter(pixels)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.bounding_box());
+        if area.size.width == 0 || area.size.height == 0 {
+            return Ok(());
+        }
+
+        let area = Rectangle::new(self.to_target(area.top_left), area.size * self.scale);
+        self.target.fill_solid(&area, color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.fill_solid(&self.bounding_box(), color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::{
+        geometry::Size,
+        pixelcolor::{Rgb565, RgbColor},
+    };
+
+    use super::*;
+    use crate::gfx::Framebuffer;
+
+    type Screen = Framebuffer<12, 10>;
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> Rectangle {
+        Rectangle::new(Point::new(x, y), Size::new(width, height))
+    }
+
+    /// 屏幕应当只在`painted`（目标坐标）处有颜色，其余为黑色
+    fn assert_painted(screen: &Screen, painted: &[(Rectangle, Rgb565)]) {
+        let mut expected = Screen::new();
+        for (area, color) in painted {
+            expected.fill_solid(area, *color).unwrap();
+        }
+        for y in 0..10 {
+            assert_eq!(screen.row(y), expected.row(y), "row {y}");
+        }
+    }
+
+    #[test]
+    fn translates_and_clips() {
+        let mut screen = Screen::new();
+        let mut view = Viewport::new(&mut screen, rect(2, 3, 6, 4));
+        assert_eq!(view.bounding_box(), rect(0, 0, 6, 4));
+
+        let pixels = [
+            Pixel(Point::new(0, 0), Rgb565::RED),
+            Pixel(Point::new(5, 3), Rgb565::GREEN),
+            // 以下都在视口外
+            Pixel(Point::new(6, 0), Rgb565::WHITE),
+            Pixel(Point::new(-1, 2), Rgb565::WHITE),
+            Pixel(Point::new(0, 4), Rgb565::WHITE),
+        ];
+        view.draw_iter(pixels).unwrap();
+        view.fill_solid(&rect(4, -2, 5, 3), Rgb565::BLUE).unwrap();
+        let colors = [Rgb565::YELLOW, Rgb565::CYAN, Rgb565::MAGENTA, Rgb565::WHITE];
+        view.fill_contiguous(&rect(-1, 2, 2, 2), colors).unwrap();
+
+        assert_eq!(view.to_target(Point::new(1, 1)), Point::new(3, 4));
+        assert_eq!(view.from_target(Point::new(7, 6)), Some(Point::new(5, 3)));
+        assert_eq!(view.from_target(Point::new(8, 6)), None);
+
+        assert_painted(
+            &screen,
+            &[
+                (rect(2, 3, 1, 1), Rgb565::RED),
+                (rect(7, 6, 1, 1), Rgb565::GREEN),
+                (rect(6, 3, 2, 1), Rgb565::BLUE),
+                (rect(2, 5, 1, 1), Rgb565::CYAN),
+                (rect(2, 6, 1, 1), Rgb565::WHITE),
+            ],
+        );
+    }
+
+    #[test]
+    fn area_is_clipped_to_target() {
+        let mut screen = Screen::new();
+        let mut view = Viewport::new(&mut screen, rect(8, 7, 10, 10));
+        assert_eq!(view.area(), rect(8, 7, 4, 3));
+        view.clear(Rgb565::RED).unwrap();
+        assert_painted(&screen, &[(rect(8, 7, 4, 3), Rgb565::RED)]);
+    }
+
+    #[test]
+    fn scales_by_integer_factor() {
+        let mut screen = Screen::new();
+        // 8x7按3倍放大，逻辑尺寸2x2，多出的行列不用
+        let mut view = Viewport::new(&mut screen, rect(1, 1, 8, 7)).scaled(3);
+        assert_eq!(view.bounding_box(), rect(0, 0, 2, 2));
+
+        view.draw_iter([
+            Pixel(Point::new(1, 0), Rgb565::RED),
+            Pixel(Point::new(2, 0), Rgb565::WHITE),
+        ])
+        .unwrap();
+        view.fill_solid(&rect(0, 1, 5, 5), Rgb565::BLUE).unwrap();
+        view.fill_contiguous(&rect(0, 0, 1, 1), [Rgb565::GREEN])
+            .unwrap();
+
+        assert_eq!(view.to_target(Point::new(1, 1)), Point::new(4, 4));
+        assert_eq!(view.from_target(Point::new(6, 3)), Some(Point::new(1, 0)));
+        assert_eq!(view.from_target(Point::new(7, 1)), None);
+
+        assert_painted(
+            &screen,
+            &[
+                (rect(1, 1, 3, 3), Rgb565::GREEN),
+                (rect(4, 1, 3, 3), Rgb565::RED),
+                (rect(1, 4, 6, 3), Rgb565::BLUE),
+            ],
+        );
+    }
+
+    #[test]
+    fn nested_viewports_compose() {
+        let mut screen = Screen::new();
+        let mut outer = Viewport::new(&mut screen, rect(2, 2, 8, 6));
+
+        // 子视口超出外层的部分被裁掉
+        let mut inner = outer.viewport(rect(6, 4, 5, 5));
+        assert_eq!(inner.area(), rect(6, 4, 2, 2));
+        inner.clear(Rgb565::RED).unwrap();
+
+        let mut inner = outer.viewport(rect(1, 1, 4, 4)).scaled(2);
+        inner
+            .draw_iter([
+                Pixel(Point::new(1, 1), Rgb565::GREEN),
+                Pixel(Point::new(2, 0), Rgb565::WHITE),
+            ])
+            .unwrap();
+        inner.fill_solid(&rect(0, 0, 1, 1), Rgb565::BLUE).unwrap();
+
+        assert_painted(
+            &screen,
+            &[
+                (rect(8, 6, 2, 2), Rgb565::RED),
+                (rect(5, 5, 2, 2), Rgb565::GREEN),
+                (rect(3, 3, 2, 2), Rgb565::BLUE),
+            ],
+        );
+    }
+}
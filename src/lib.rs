@@ -4,3 +4,6 @@ pub use adapter::*;
 
 pub mod driver;
 pub use driver::*;
+
+pub mod gfx;
+pub use gfx::*;